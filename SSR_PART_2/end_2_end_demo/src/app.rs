@@ -1,11 +1,14 @@
 use leptos::prelude::*;
 use leptos_meta::{provide_meta_context, MetaTags, Stylesheet, Title};
 use leptos_router::{
-    components::{Route, Router, Routes},
+    components::{ParentRoute, Route, Router, Routes, A},
     path,
 };
 
+use crate::components::login_form::{provide_auth, LoginForm, UserMenu};
 use crate::components::users_lists_sig::UsersListSig;
+use crate::components::users_trash::UsersTrash;
+use crate::components::workspace_list::{WorkspaceDetail, WorkspaceList};
 
 pub fn shell(options: LeptosOptions) -> impl IntoView {
     view! {
@@ -29,6 +32,8 @@ pub fn shell(options: LeptosOptions) -> impl IntoView {
 pub fn App() -> impl IntoView {
     // Provides context that manages stylesheets, titles, meta tags, etc.
     provide_meta_context();
+    // 登录状态在所有页面共享
+    provide_auth();
 
     view! {
         // injects a stylesheet into the document <head>
@@ -39,9 +44,21 @@ pub fn App() -> impl IntoView {
 
         // content for this welcome page
         <Router>
+            <nav>
+                <A href="/">"用户"</A>" | "
+                <A href="/workspaces">"分组"</A>" | "
+                <A href="/trash">"回收站"</A>" | "
+                <UserMenu/>
+            </nav>
             <main>
                 <Routes fallback=|| "Page not found.".into_view()>
                     <Route path=path!("/") view=HomePage/>
+                    <Route path=path!("/login") view=LoginForm/>
+                    <Route path=path!("/trash") view=UsersTrash/>
+                    <ParentRoute path=path!("/workspaces") view=WorkspaceList>
+                        <Route path=path!("") view=|| view! { <p>"请选择一个分组。"</p> }/>
+                        <Route path=path!(":id") view=WorkspaceDetail/>
+                    </ParentRoute>
                 </Routes>
             </main>
         </Router>
@@ -0,0 +1,30 @@
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+
+use crate::auth::password::{hash_password, MIN_PASSWORD_LEN};
+use crate::entity::users;
+
+/// Runs the `set-password <email> <password>` subcommand, e.g. to give the seeded `super@none.com`
+/// a password so that someone can log in and create the other users.
+pub async fn run_set_password(db: &DatabaseConnection, args: &[String]) -> anyhow::Result<()> {
+    let (Some(email), Some(password)) = (args.first(), args.get(1)) else {
+        anyhow::bail!("usage: set-password <email> <password>");
+    };
+    if password.chars().count() < MIN_PASSWORD_LEN {
+        anyhow::bail!("password must be at least {} characters", MIN_PASSWORD_LEN);
+    }
+
+    let user = users::Entity::find()
+        .filter(users::Column::Email.eq(email.as_str()))
+        .one(db)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("no user with email {}", email))?;
+
+    let password_hash =
+        hash_password(password).map_err(|e| anyhow::anyhow!("failed to hash password: {}", e))?;
+    let mut user: users::ActiveModel = user.into();
+    user.password_hash = Set(password_hash);
+    user.update(db).await?;
+
+    tracing::info!("password set for user {}", email);
+    Ok(())
+}
@@ -0,0 +1,3 @@
+pub mod command;
+pub mod password;
+pub mod session;
@@ -0,0 +1,95 @@
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+
+/// Minimum length of a user password.
+pub const MIN_PASSWORD_LEN: usize = 8;
+
+/// Hash of a password nobody knows, with the parameters of [`hash_password`]. Logins without a
+/// usable hash are checked against it, so they take as long as the others, see
+/// [`verify_password_blocking`].
+const DUMMY_HASH: &str =
+    "$argon2id$v=19$m=19456,t=2,p=1$2LCSl0+h6CieUMZIbh3CLQ$BWvi1f6NXspHgiuYbbRNF07gYnhqTXDfehfihTPbgq8";
+
+/// Hashes `password` with argon2id into a PHC string, e.g. `$argon2id$v=19$m=19456,t=2,p=1$...`.
+///
+/// With the default parameters the result is at most 97 characters, matching `users.password_hash VARCHAR(97)`.
+pub fn hash_password(password: &str) -> Result<String, argon2::password_hash::Error> {
+    let salt = SaltString::generate(&mut OsRng);
+    let hash = Argon2::default().hash_password(password.as_bytes(), &salt)?;
+    Ok(hash.to_string())
+}
+
+/// Checks `password` against a PHC string produced by [`hash_password`].
+///
+/// Returns `false` for malformed hashes, e.g. the empty hashes of the seeded users.
+pub fn verify_password(password: &str, password_hash: &str) -> bool {
+    PasswordHash::new(password_hash)
+        .map(|hash| {
+            Argon2::default()
+                .verify_password(password.as_bytes(), &hash)
+                .is_ok()
+        })
+        .unwrap_or(false)
+}
+
+/// Runs [`hash_password`] on the blocking thread pool, so it does not hold up the async runtime.
+pub async fn hash_password_blocking(
+    password: String,
+) -> Result<String, argon2::password_hash::Error> {
+    tokio::task::spawn_blocking(move || hash_password(&password))
+        .await
+        .expect("hashing the password panicked")
+}
+
+/// Runs [`verify_password`] on the blocking thread pool, so it does not hold up the async runtime.
+///
+/// Without a usable `password_hash`, e.g. for an unknown email or a seeded user without a
+/// password, `password` is checked against a dummy hash and `false` returned, so the time taken
+/// does not tell those apart from a wrong password.
+pub async fn verify_password_blocking(password: String, password_hash: Option<String>) -> bool {
+    tokio::task::spawn_blocking(move || {
+        match password_hash.filter(|hash| PasswordHash::new(hash).is_ok()) {
+            Some(hash) => verify_password(&password, &hash),
+            None => {
+                verify_password(&password, DUMMY_HASH);
+                false
+            }
+        }
+    })
+    .await
+    .expect("verifying the password panicked")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_and_verify_password() {
+        let hash = hash_password("correct horse").unwrap();
+
+        assert!(hash.starts_with("$argon2id$"));
+        assert!(hash.len() <= 97);
+        assert!(verify_password("correct horse", &hash));
+        assert!(!verify_password("wrong horse", &hash));
+        assert!(!verify_password("correct horse", ""));
+    }
+
+    #[test]
+    fn test_dummy_hash_has_default_parameters() {
+        let hash = hash_password("correct horse").unwrap();
+        let params = |hash: &str| hash.rsplitn(3, '$').nth(2).unwrap().to_string();
+        assert_eq!(params(DUMMY_HASH), params(&hash));
+    }
+
+    #[tokio::test]
+    async fn test_verify_password_blocking() {
+        let hash = hash_password("correct horse").unwrap();
+
+        assert!(verify_password_blocking("correct horse".to_string(), Some(hash.clone())).await);
+        assert!(!verify_password_blocking("wrong horse".to_string(), Some(hash)).await);
+        assert!(!verify_password_blocking("correct horse".to_string(), Some(String::new())).await);
+        assert!(!verify_password_blocking("correct horse".to_string(), None).await);
+    }
+}
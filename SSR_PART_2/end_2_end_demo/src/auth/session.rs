@@ -0,0 +1,257 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use axum::http::header::{COOKIE, SET_COOKIE};
+use axum::http::request::Parts;
+use axum::http::{HeaderMap, HeaderValue, StatusCode};
+use leptos::prelude::{provide_context, use_context};
+use leptos_axum::ResponseOptions;
+
+use crate::config::AppConfig;
+use crate::dto::auth_user::AuthUser;
+use crate::errors::user_error::UserError;
+use crate::state::app_state::AppState;
+
+/// Name of the cookie holding the session token.
+pub const SESSION_COOKIE: &str = "session_id";
+
+/// How long a session stays valid after login.
+pub const SESSION_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// How often [`SessionStore::spawn_sweep`] removes the expired sessions.
+pub const SESSION_SWEEP_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Debug, Clone)]
+struct Session {
+    user: AuthUser,
+    expires_at: Instant,
+}
+
+/// In-memory server-side session store, keyed by the random token stored in [`SESSION_COOKIE`].
+///
+/// Sessions are lost when the server restarts.
+#[derive(Debug, Clone, Default)]
+pub struct SessionStore {
+    sessions: Arc<RwLock<HashMap<String, Session>>>,
+}
+
+impl SessionStore {
+    /// Starts a session for `user` and returns its token.
+    pub fn create(&self, user: AuthUser) -> String {
+        let mut bytes = [0u8; 32];
+        OsRng.fill_bytes(&mut bytes);
+        let token: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+
+        let session = Session {
+            user,
+            expires_at: Instant::now() + SESSION_TTL,
+        };
+        self.sessions
+            .write()
+            .unwrap()
+            .insert(token.clone(), session);
+        token
+    }
+
+    /// Returns the user of a live session; expired sessions are removed.
+    pub fn get(&self, token: &str) -> Option<AuthUser> {
+        let mut sessions = self.sessions.write().unwrap();
+        match sessions.get(token) {
+            Some(session) if session.expires_at > Instant::now() => Some(session.user.clone()),
+            Some(_) => {
+                sessions.remove(token);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Ends the session with the given token.
+    pub fn remove(&self, token: &str) {
+        self.sessions.write().unwrap().remove(token);
+    }
+
+    /// Ends every session of user `user_id` but the one with token `keep`, if any.
+    pub fn remove_user(&self, user_id: i64, keep: Option<&str>) {
+        self.sessions
+            .write()
+            .unwrap()
+            .retain(|token, session| session.user.id != user_id || Some(token.as_str()) == keep);
+    }
+
+    /// Removes the expired sessions, which [`SessionStore::get`] only removes when they are used.
+    pub fn remove_expired(&self) {
+        let now = Instant::now();
+        self.sessions
+            .write()
+            .unwrap()
+            .retain(|_, session| session.expires_at > now);
+    }
+
+    /// Runs [`SessionStore::remove_expired`] every [`SESSION_SWEEP_INTERVAL`] in the background, so
+    /// sessions that are never used again do not pile up.
+    pub fn spawn_sweep(&self) -> tokio::task::JoinHandle<()> {
+        let store = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(SESSION_SWEEP_INTERVAL);
+            loop {
+                interval.tick().await;
+                store.remove_expired();
+            }
+        })
+    }
+}
+
+/// The user authenticated for the current request, `None` for anonymous requests.
+#[derive(Debug, Clone, Default)]
+pub struct CurrentUser(pub Option<AuthUser>);
+
+/// Resolves the session cookie of the current request and provides the result as [`CurrentUser`].
+///
+/// Called for every request from the context closure in `main.rs`, after the request parts are provided.
+pub fn provide_current_user(state: &AppState) {
+    let user = session_token().and_then(|token| state.sessions().get(&token));
+    provide_context(CurrentUser(user));
+}
+
+/// Returns the authenticated user of the current request, if any.
+pub fn current_user() -> Option<AuthUser> {
+    use_context::<CurrentUser>().and_then(|current| current.0)
+}
+
+/// Returns the authenticated user, or [`UserError::Unauthorized`] with a `401` response status.
+pub fn require_user() -> Result<AuthUser, UserError> {
+    current_user().ok_or_else(|| {
+        if let Some(res) = use_context::<ResponseOptions>() {
+            res.set_status(StatusCode::UNAUTHORIZED);
+        }
+        UserError::Unauthorized
+    })
+}
+
+/// Reads the session token from the `Cookie` headers of the current request.
+pub fn session_token() -> Option<String> {
+    let parts = use_context::<Parts>()?;
+    session_token_from_headers(&parts.headers)
+}
+
+/// Reads the session token from `Cookie` headers, for plain Axum handlers outside of Leptos.
+pub fn session_token_from_headers(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get_all(COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(name, _)| *name == SESSION_COOKIE)
+        .map(|(_, token)| token.to_string())
+}
+
+/// Sends the session cookie for `token` with the response.
+pub fn set_session_cookie(token: &str) {
+    append_cookie(&session_cookie(
+        token,
+        SESSION_TTL.as_secs(),
+        AppConfig::get().security().secure_cookies(),
+    ));
+}
+
+/// Tells the browser to drop the session cookie.
+pub fn clear_session_cookie() {
+    append_cookie(&session_cookie(
+        "",
+        0,
+        AppConfig::get().security().secure_cookies(),
+    ));
+}
+
+/// The `Set-Cookie` value of the session cookie; `secure` limits it to HTTPS.
+fn session_cookie(token: &str, max_age: u64, secure: bool) -> String {
+    format!(
+        "{}={}; Path=/; HttpOnly; SameSite=Lax; Max-Age={}{}",
+        SESSION_COOKIE,
+        token,
+        max_age,
+        if secure { "; Secure" } else { "" }
+    )
+}
+
+fn append_cookie(cookie: &str) {
+    if let (Some(res), Ok(value)) = (
+        use_context::<ResponseOptions>(),
+        HeaderValue::from_str(cookie),
+    ) {
+        res.append_header(SET_COOKIE, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_store() {
+        let store = SessionStore::default();
+        let user = AuthUser::new(7, "Bobby".to_string(), "bobby@none.co".to_string(), 0);
+
+        let token = store.create(user.clone());
+        assert_eq!(token.len(), 64);
+        assert_eq!(store.get(&token), Some(user));
+        assert_eq!(store.get("unknown"), None);
+
+        store.remove(&token);
+        assert_eq!(store.get(&token), None);
+    }
+
+    #[test]
+    fn test_remove_user_sessions() {
+        let store = SessionStore::default();
+        let user = AuthUser::new(7, "Bobby".to_string(), "bobby@none.co".to_string(), 0);
+        let other = AuthUser::new(9, "Dora".to_string(), "dora@none.co".to_string(), 5);
+
+        let current = store.create(user.clone());
+        let stale = store.create(user.clone());
+        let others = store.create(other.clone());
+        store.remove_user(7, Some(&current));
+        assert_eq!(store.get(&current), Some(user));
+        assert_eq!(store.get(&stale), None);
+        assert_eq!(store.get(&others), Some(other));
+
+        store.remove_user(7, None);
+        assert_eq!(store.get(&current), None);
+    }
+
+    #[test]
+    fn test_remove_expired_sessions() {
+        let store = SessionStore::default();
+        let user = AuthUser::new(7, "Bobby".to_string(), "bobby@none.co".to_string(), 0);
+        let live = store.create(user.clone());
+        let expired = store.create(user);
+        store
+            .sessions
+            .write()
+            .unwrap()
+            .get_mut(&expired)
+            .unwrap()
+            .expires_at = Instant::now();
+
+        store.remove_expired();
+        let sessions = store.sessions.read().unwrap();
+        assert!(sessions.contains_key(&live));
+        assert!(!sessions.contains_key(&expired));
+    }
+
+    #[test]
+    fn test_session_cookie() {
+        assert_eq!(
+            session_cookie("abc", 60, true),
+            "session_id=abc; Path=/; HttpOnly; SameSite=Lax; Max-Age=60; Secure"
+        );
+        assert_eq!(
+            session_cookie("", 0, false),
+            "session_id=; Path=/; HttpOnly; SameSite=Lax; Max-Age=0"
+        );
+    }
+}
@@ -0,0 +1,106 @@
+use leptos::prelude::*;
+
+use crate::dto::audit_action::AuditAction;
+use crate::server_fn::audit_log::{get_audit_log, AUDIT_LOG_PAGE_SIZE};
+
+/// 用户的变更历史，按时间倒序分页显示
+#[component]
+pub fn AuditLogPanel(user_id: i64, on_close: Callback<()>) -> impl IntoView {
+    let (page, set_page) = signal(1_u64);
+    let audit_log = Resource::new(move || page.get(), move |page| get_audit_log(user_id, page));
+
+    let total_pages = move || {
+        audit_log
+            .get()
+            .and_then(|rt| rt.ok())
+            .map(|log| log.total.div_ceil(AUDIT_LOG_PAGE_SIZE).max(1))
+            .unwrap_or(1)
+    };
+
+    view! {
+        <div class="users-form">
+            <div class="close" on:click=move |_| on_close.run(())>"关闭"</div>
+            <h3>{format!("用户 {} 的变更历史", user_id)}</h3>
+            <Suspense fallback=move || view! { <p>"加载中..."</p> }>
+                {move || {
+                    audit_log
+                        .get()
+                        .map(|rt| match rt {
+                            Ok(log) if log.items.is_empty() => {
+                                view! { <p>"没有变更记录。"</p> }.into_any()
+                            }
+                            Ok(log) => {
+                                view! {
+                                    <table>
+                                        <thead>
+                                            <tr>
+                                                <th>"时间"</th>
+                                                <th>"操作人"</th>
+                                                <th>"操作"</th>
+                                                <th>"变更"</th>
+                                            </tr>
+                                        </thead>
+                                        <tbody>
+                                            {log
+                                                .items
+                                                .into_iter()
+                                                .map(|entry| {
+                                                    let action = AuditAction::from_param(&entry.action)
+                                                        .map(|action| action.label().to_string())
+                                                        .unwrap_or_else(|| entry.action.clone());
+                                                    let actor = entry
+                                                        .actor_name
+                                                        .clone()
+                                                        .unwrap_or_else(|| format!("id {}", entry.actor_id));
+                                                    let changes = entry.changes();
+                                                    view! {
+                                                        <tr>
+                                                            <td>{entry.create_at}</td>
+                                                            <td>{actor}</td>
+                                                            <td>{action}</td>
+                                                            <td>
+                                                                <ul>
+                                                                    {changes
+                                                                        .into_iter()
+                                                                        .map(|change| {
+                                                                            view! {
+                                                                                <li>
+                                                                                    {format!(
+                                                                                        "{}: {} → {}",
+                                                                                        change.field,
+                                                                                        change.before,
+                                                                                        change.after,
+                                                                                    )}
+                                                                                </li>
+                                                                            }
+                                                                        })
+                                                                        .collect_view()}
+                                                                </ul>
+                                                            </td>
+                                                        </tr>
+                                                    }
+                                                })
+                                                .collect_view()}
+                                        </tbody>
+                                    </table>
+                                }
+                                    .into_any()
+                            }
+                            Err(e) => view! { <p class="error">{e.to_string()}</p> }.into_any(),
+                        })
+                }}
+            </Suspense>
+            <div class="pager">
+                <button
+                    disabled=move || page.get() <= 1
+                    on:click=move |_| set_page.update(|page| *page -= 1)
+                >"上一页"</button>
+                <span>{move || format!("第 {} / {} 页", page.get(), total_pages())}</span>
+                <button
+                    disabled=move || page.get() >= total_pages()
+                    on:click=move |_| set_page.update(|page| *page += 1)
+                >"下一页"</button>
+            </div>
+        </div>
+    }
+}
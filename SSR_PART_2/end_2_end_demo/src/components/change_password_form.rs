@@ -0,0 +1,42 @@
+use leptos::prelude::*;
+
+use crate::server_fn::user::ChangePassword;
+
+/// 修改已有用户的密码，需要先输入当前密码
+#[component]
+pub fn ChangePasswordForm(user_id: i64) -> impl IntoView {
+    let change_password = ServerAction::<ChangePassword>::new();
+
+    let result_msg = move || {
+        change_password.value().get().map(|rt| match rt {
+            Ok(()) => view! { <p>"密码已修改。"</p> }.into_any(),
+            Err(e) => view! { <p class="error">{e.to_string()}</p> }.into_any(),
+        })
+    };
+
+    view! {
+        <ActionForm action=change_password>
+            <input type="hidden" name="id" value=user_id/>
+            {result_msg}
+            <div class="form_div">
+                <label>
+                    "Current Password"
+                    <input type="password" name="old_password" autocomplete="current-password"/>
+                </label>
+            </div>
+            <div class="form_div">
+                <label>
+                    "New Password"
+                    <input type="password" name="new_password" autocomplete="new-password"/>
+                </label>
+            </div>
+            <div class="form_div">
+                <label>
+                    "Confirm New Password"
+                    <input type="password" name="new_password_confirm" autocomplete="new-password"/>
+                </label>
+            </div>
+            <input type="submit" value="修改密码"/>
+        </ActionForm>
+    }
+}
@@ -0,0 +1,579 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+
+use leptos::ev::{FocusEvent, KeyboardEvent};
+use leptos::html;
+use leptos::prelude::*;
+
+use crate::dto::users_query::SortDirection;
+
+/// The sort key and direction a [`DataGrid`] is sorted by, `None` if it is not sorted.
+pub type SortState<K> = Option<(K, SortDirection)>;
+
+/// A row of a [`DataGrid`], identified by a key that stays the same when the row changes.
+pub trait GridRow: Clone + Send + Sync + 'static {
+    type Key: Clone + Eq + Hash + Send + Sync + 'static;
+
+    fn key(&self) -> Self::Key;
+}
+
+/// A column of a [`DataGrid`] showing rows of type `T`.
+pub trait GridColumn<T>: Clone + Send + Sync + 'static {
+    /// What the grid is sorted by when the header of a sortable column is clicked; `()` if no
+    /// column of the grid is sortable.
+    type SortKey: Copy + PartialEq + Send + Sync + 'static;
+
+    fn title(&self) -> &'static str;
+
+    /// The sort key of this column, `None` if it is not sortable.
+    fn sort_key(&self) -> Option<Self::SortKey> {
+        None
+    }
+
+    /// Renders the cell of `row` in this column.
+    fn cell(&self, row: &T) -> AnyView;
+
+    /// How the cells of this column are edited inline, `None` if they cannot be.
+    fn editor(&self) -> Option<CellEditor> {
+        None
+    }
+
+    /// The value of the cell of `row` shown in its editor, read without tracking.
+    fn value(&self, _row: &T) -> String {
+        String::new()
+    }
+}
+
+/// The input shown in place of a cell while it is edited inline.
+#[derive(Debug, Clone, Copy)]
+pub enum CellEditor {
+    Text,
+    /// A drop-down list of `(value, label)` options.
+    Select(Signal<Vec<(String, String)>>),
+}
+
+/// A cell edited inline, passed to `on_cell_edit` when it is committed with a changed value.
+#[derive(Debug, Clone)]
+pub struct CellEdit<T, C> {
+    pub row: T,
+    pub column: C,
+    pub value: String,
+}
+
+/// A button shown in the last column of every row of a [`DataGrid`].
+#[derive(Clone)]
+pub struct RowAction<K: 'static> {
+    pub label: &'static str,
+    pub on_click: Callback<K>,
+}
+
+impl<K: 'static> RowAction<K> {
+    pub fn new(label: &'static str, on_click: impl Fn(K) + Send + Sync + 'static) -> Self {
+        Self {
+            label,
+            on_click: Callback::new(on_click),
+        }
+    }
+}
+
+/// 通用表格：按 key 渲染行，可选勾选、排序、点击行编辑、单元格内联编辑和每行的操作按钮
+#[component]
+pub fn DataGrid<T, C>(
+    /// The rows, rendered in order and keyed by [`GridRow::key`].
+    #[prop(into)]
+    rows: Signal<Vec<T>>,
+    columns: Vec<C>,
+    /// Keys of the checked rows; adds a checkbox column when set.
+    #[prop(optional)]
+    selection: Option<RwSignal<HashSet<T::Key>>>,
+    /// The column the rows are currently sorted by, shown with an arrow in its header.
+    #[prop(optional, into)]
+    sorted: Option<Signal<SortState<C::SortKey>>>,
+    /// Called with the sort key of a sortable column when its header is clicked.
+    #[prop(optional)]
+    on_sort: Option<Callback<C::SortKey>>,
+    /// Called with a row when it is clicked.
+    #[prop(optional)]
+    on_edit: Option<Callback<T>>,
+    /// Buttons of every row, shown in order in a last column when not empty.
+    #[prop(optional)]
+    actions: Vec<RowAction<T::Key>>,
+    /// Called when an inline edit is committed; cells of columns with an
+    /// [`editor`](GridColumn::editor) are edited by double-clicking them when set.
+    #[prop(optional)]
+    on_cell_edit: Option<Callback<CellEdit<T, C>>>,
+) -> impl IntoView
+where
+    T: GridRow,
+    C: GridColumn<T>,
+{
+    let has_actions = !actions.is_empty();
+    let columns = StoredValue::new(columns);
+    let actions = StoredValue::new(actions);
+
+    let all_checked = move || {
+        let Some(selection) = selection else {
+            return false;
+        };
+        rows.with(|rows| {
+            !rows.is_empty()
+                && selection.with(|keys| rows.iter().all(|row| keys.contains(&row.key())))
+        })
+    };
+    let toggle_all = move |checked: bool| {
+        if let Some(selection) = selection {
+            selection.set(if checked {
+                rows.with_untracked(|rows| rows.iter().map(GridRow::key).collect())
+            } else {
+                HashSet::new()
+            });
+        }
+    };
+
+    let sort_indicator = move |key: C::SortKey| match sorted.and_then(|sorted| sorted.get()) {
+        Some((current, SortDirection::Asc)) if current == key => " ▲",
+        Some((current, SortDirection::Desc)) if current == key => " ▼",
+        _ => "",
+    };
+
+    let header = columns.with_value(|columns| {
+        columns
+            .iter()
+            .map(|column| match column.sort_key() {
+                Some(key) => view! {
+                    <th class="sortable" on:click=move |_| {
+                        if let Some(on_sort) = on_sort {
+                            on_sort.run(key);
+                        }
+                    }>
+                        {column.title()}{move || sort_indicator(key)}
+                    </th>
+                }
+                .into_any(),
+                None => view! { <th>{column.title()}</th> }.into_any(),
+            })
+            .collect_view()
+    });
+
+    let edit = InlineEdit {
+        rows,
+        columns,
+        editing: RwSignal::new(None),
+        draft: RwSignal::new(String::new()),
+        on_cell_edit,
+    };
+    let InlineEdit { editing, draft, .. } = edit;
+
+    let editor_view = move |row: T, index: usize, editor: CellEditor| {
+        let is_text = matches!(editor, CellEditor::Text);
+        let key_row = row.clone();
+        // 回车提交，Esc 取消，Tab / Shift+Tab 移到后 / 前一个可编辑单元格，上下方向键移到上 / 下一行
+        let on_keydown = move |ev: KeyboardEvent| {
+            let step = match ev.key().as_str() {
+                "Enter" => Move::Stay,
+                "Tab" if ev.shift_key() => Move::Previous,
+                "Tab" => Move::Next,
+                "ArrowUp" if is_text => Move::Up,
+                "ArrowDown" if is_text => Move::Down,
+                "Escape" => {
+                    ev.prevent_default();
+                    editing.set(None);
+                    return;
+                }
+                _ => return,
+            };
+            ev.prevent_default();
+            edit.finish(key_row.clone(), index, step);
+        };
+        let on_blur = move |_: FocusEvent| edit.finish(row.clone(), index, Move::Stay);
+
+        match editor {
+            CellEditor::Text => {
+                let input = NodeRef::<html::Input>::new();
+                Effect::new(move || {
+                    if let Some(input) = input.get() {
+                        let _ = input.focus();
+                        input.select();
+                    }
+                });
+                view! {
+                    <input type="text"
+                        node_ref=input
+                        prop:value=move || draft.get()
+                        on:input:target=move |ev| draft.set(ev.target().value())
+                        on:keydown=on_keydown
+                        on:blur=on_blur
+                    />
+                }
+                .into_any()
+            }
+            CellEditor::Select(options) => {
+                let select = NodeRef::<html::Select>::new();
+                Effect::new(move || {
+                    if let Some(select) = select.get() {
+                        let _ = select.focus();
+                    }
+                });
+                view! {
+                    <select
+                        node_ref=select
+                        on:change:target=move |ev| draft.set(ev.target().value())
+                        on:keydown=on_keydown
+                        on:blur=on_blur
+                    >
+                        {move || {
+                            options
+                                .get()
+                                .into_iter()
+                                .map(|(value, label)| {
+                                    let selected = draft.with_untracked(|draft| *draft == value);
+                                    view! { <option value=value selected=selected>{label}</option> }
+                                })
+                                .collect_view()
+                        }}
+                    </select>
+                }
+                .into_any()
+            }
+        }
+    };
+
+    // 可编辑的单元格：单击不会触发行的 on_edit，双击开始编辑
+    let editable_cell = move |row: T, index: usize, column: C, editor: CellEditor| {
+        let key = row.key();
+        let is_editing = Memo::new(move |_| {
+            editing.with(|editing| matches!(editing, Some((k, i)) if *k == key && *i == index))
+        });
+        let edit_row = row.clone();
+        view! {
+            <td class="editable"
+                on:click=|ev| ev.stop_propagation()
+                on:dblclick=move |_| {
+                    if !is_editing.get_untracked() {
+                        edit.start(&edit_row, index);
+                    }
+                }
+            >
+                {move || {
+                    if is_editing.get() {
+                        editor_view(row.clone(), index, editor)
+                    } else {
+                        column.cell(&row)
+                    }
+                }}
+            </td>
+        }
+    };
+
+    let row_view = move |row: T| {
+        let key = row.key();
+        let cells = columns.with_value(|columns| {
+            columns
+                .iter()
+                .enumerate()
+                .map(
+                    |(index, column)| match column.editor().filter(|_| on_cell_edit.is_some()) {
+                        Some(editor) => {
+                            editable_cell(row.clone(), index, column.clone(), editor).into_any()
+                        }
+                        None => view! { <td>{column.cell(&row)}</td> }.into_any(),
+                    },
+                )
+                .collect_view()
+        });
+        let checkbox = selection.map(|selection| {
+            let key = key.clone();
+            let checked_key = key.clone();
+            view! {
+                <td>
+                    <input type="checkbox"
+                        prop:checked=move || selection.with(|keys| keys.contains(&checked_key))
+                        on:click=|ev| ev.stop_propagation()
+                        on:change:target=move |ev| {
+                            let checked = ev.target().checked();
+                            selection.update(|keys| {
+                                if checked {
+                                    keys.insert(key.clone());
+                                } else {
+                                    keys.remove(&key);
+                                }
+                            });
+                        }
+                    />
+                </td>
+            }
+        });
+        let buttons = has_actions.then(|| {
+            let action_buttons = actions.with_value(|actions| {
+                actions
+                    .iter()
+                    .map(|action| {
+                        let key = key.clone();
+                        let on_click = action.on_click;
+                        view! {
+                            <button on:click=move |ev| {
+                                ev.stop_propagation();
+                                on_click.run(key.clone());
+                            }>{action.label}</button>
+                        }
+                    })
+                    .collect_view()
+            });
+            view! { <td>{action_buttons}</td> }
+        });
+
+        view! {
+            <tr on:click=move |_| {
+                if let Some(on_edit) = on_edit {
+                    on_edit.run(row.clone());
+                }
+            }>
+                {checkbox}
+                {cells}
+                {buttons}
+            </tr>
+        }
+    };
+
+    view! {
+        <table>
+            <thead>
+                <tr>
+                    {selection.map(|_| view! {
+                        <th>
+                            <input type="checkbox"
+                                prop:checked=all_checked
+                                on:change:target=move |ev| toggle_all(ev.target().checked())
+                            />
+                        </th>
+                    })}
+                    {header}
+                    {has_actions.then(|| view! { <th>"操作"</th> })}
+                </tr>
+            </thead>
+            <tbody>
+                <For
+                    each=move || rows.get()
+                    key=|row: &T| row.key()
+                    children=row_view
+                />
+            </tbody>
+        </table>
+    }
+}
+
+/// The state of the cell edited inline in a [`DataGrid`].
+struct InlineEdit<T: GridRow, C: 'static> {
+    rows: Signal<Vec<T>>,
+    columns: StoredValue<Vec<C>>,
+    // 正在编辑的单元格（行 key，列序号）和输入框中的值
+    editing: RwSignal<Option<(T::Key, usize)>>,
+    draft: RwSignal<String>,
+    on_cell_edit: Option<Callback<CellEdit<T, C>>>,
+}
+
+impl<T: GridRow, C> Clone for InlineEdit<T, C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: GridRow, C> Copy for InlineEdit<T, C> {}
+
+impl<T: GridRow, C: GridColumn<T>> InlineEdit<T, C> {
+    fn start(&self, row: &T, index: usize) {
+        self.draft
+            .set(self.columns.with_value(|columns| columns[index].value(row)));
+        self.editing.set(Some((row.key(), index)));
+    }
+
+    // 提交编辑，然后按 step 移动到其它单元格；按回车后输入框消失触发的 blur 不会重复提交
+    fn finish(&self, row: T, index: usize, step: Move) {
+        let key = row.key();
+        if !self
+            .editing
+            .with_untracked(|editing| matches!(editing, Some((k, i)) if *k == key && *i == index))
+        {
+            return;
+        }
+
+        // 先取出当前单元格的值，开始编辑下一个单元格会覆盖 draft
+        let column = self.columns.with_value(|columns| columns[index].clone());
+        let value = self.draft.get_untracked();
+        let edit = (value != column.value(&row)).then(|| CellEdit {
+            row: row.clone(),
+            column,
+            value,
+        });
+
+        let target = self.rows.with_untracked(|rows| {
+            let position = rows.iter().position(|row| row.key() == key)?;
+            let editable = self.columns.with_value(|columns| {
+                columns
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, column)| column.editor().is_some())
+                    .map(|(index, _)| index)
+                    .collect::<Vec<_>>()
+            });
+            let (target_row, target_column) =
+                target_cell(rows.len(), &editable, (position, index), step)?;
+            Some((rows[target_row].clone(), target_column))
+        });
+        match target {
+            Some((row, index)) => self.start(&row, index),
+            None => self.editing.set(None),
+        }
+
+        if let (Some(edit), Some(on_cell_edit)) = (edit, self.on_cell_edit) {
+            on_cell_edit.run(edit);
+        }
+    }
+}
+
+/// Where to go after committing an inline edit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Move {
+    /// Stop editing.
+    Stay,
+    Next,
+    Previous,
+    Up,
+    Down,
+}
+
+/// The cell `step` moves to from cell `(row, column)`, among `rows` rows whose `editable` columns
+/// can be edited; `None` at the edges of the grid.
+fn target_cell(
+    rows: usize,
+    editable: &[usize],
+    (row, column): (usize, usize),
+    step: Move,
+) -> Option<(usize, usize)> {
+    let position = editable.iter().position(|index| *index == column)?;
+    let (row, position) = match step {
+        Move::Stay => return None,
+        Move::Next if position + 1 < editable.len() => (row, position + 1),
+        Move::Next => (row + 1, 0),
+        Move::Previous if position > 0 => (row, position - 1),
+        Move::Previous => (row.checked_sub(1)?, editable.len() - 1),
+        Move::Up => (row.checked_sub(1)?, position),
+        Move::Down => (row + 1, position),
+    };
+    (row < rows).then(|| (row, editable[position]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_target_cell() {
+        let editable = [1, 3, 4];
+        assert_eq!(target_cell(2, &editable, (0, 1), Move::Stay), None);
+        assert_eq!(target_cell(2, &editable, (0, 1), Move::Next), Some((0, 3)));
+        assert_eq!(target_cell(2, &editable, (0, 4), Move::Next), Some((1, 1)));
+        assert_eq!(target_cell(2, &editable, (1, 4), Move::Next), None);
+        assert_eq!(
+            target_cell(2, &editable, (1, 1), Move::Previous),
+            Some((0, 4))
+        );
+        assert_eq!(target_cell(2, &editable, (0, 1), Move::Previous), None);
+        assert_eq!(target_cell(2, &editable, (1, 3), Move::Up), Some((0, 3)));
+        assert_eq!(target_cell(2, &editable, (0, 3), Move::Down), Some((1, 3)));
+        assert_eq!(target_cell(2, &editable, (1, 3), Move::Down), None);
+        assert_eq!(target_cell(2, &editable, (0, 2), Move::Next), None);
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Row {
+        id: i32,
+        fullname: String,
+        email: String,
+    }
+
+    impl GridRow for Row {
+        type Key = i32;
+
+        fn key(&self) -> i32 {
+            self.id
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Column {
+        Fullname,
+        Email,
+    }
+
+    impl GridColumn<Row> for Column {
+        type SortKey = ();
+
+        fn title(&self) -> &'static str {
+            ""
+        }
+
+        fn cell(&self, _row: &Row) -> AnyView {
+            ().into_any()
+        }
+
+        fn editor(&self) -> Option<CellEditor> {
+            Some(CellEditor::Text)
+        }
+
+        fn value(&self, row: &Row) -> String {
+            match self {
+                Column::Fullname => row.fullname.clone(),
+                Column::Email => row.email.clone(),
+            }
+        }
+    }
+
+    fn row(id: i32) -> Row {
+        Row {
+            id,
+            fullname: format!("user {id}"),
+            email: format!("user{id}@example.com"),
+        }
+    }
+
+    #[test]
+    fn test_finish_edit_commits_the_current_cell() {
+        let owner = Owner::new();
+        owner.with(|| {
+            let edits = RwSignal::new(Vec::<CellEdit<Row, Column>>::new());
+            let edit = InlineEdit {
+                rows: Signal::stored(vec![row(1), row(2)]),
+                columns: StoredValue::new(vec![Column::Fullname, Column::Email]),
+                editing: RwSignal::new(None),
+                draft: RwSignal::new(String::new()),
+                on_cell_edit: Some(Callback::new(move |edit| edits.write().push(edit))),
+            };
+
+            edit.start(&row(1), 0);
+            edit.draft.set("renamed".to_string());
+            edit.finish(row(1), 0, Move::Next);
+            assert_eq!(edit.editing.get_untracked(), Some((1, 1)));
+            assert_eq!(edit.draft.get_untracked(), "user1@example.com");
+
+            edit.draft.set("new@example.com".to_string());
+            edit.finish(row(1), 1, Move::Down);
+            assert_eq!(edit.editing.get_untracked(), Some((2, 1)));
+            assert_eq!(edit.draft.get_untracked(), "user2@example.com");
+
+            edit.finish(row(2), 1, Move::Stay);
+            assert_eq!(edit.editing.get_untracked(), None);
+
+            let edits = edits.get_untracked();
+            assert_eq!(edits.len(), 2);
+            assert_eq!(
+                (edits[0].row.id, edits[0].column, edits[0].value.as_str()),
+                (1, Column::Fullname, "renamed")
+            );
+            assert_eq!(
+                (edits[1].row.id, edits[1].column, edits[1].value.as_str()),
+                (1, Column::Email, "new@example.com")
+            );
+        });
+    }
+}
@@ -0,0 +1,80 @@
+use leptos::prelude::*;
+
+/// 显示 `text`，其中与 `q` 匹配（不区分大小写）的部分用 `<mark>` 高亮
+#[component]
+pub fn Highlight(
+    #[prop(into)] text: Signal<String>,
+    #[prop(into)] q: Signal<Option<String>>,
+) -> impl IntoView {
+    move || {
+        let q = q.get().unwrap_or_default();
+        split_matches(&text.get(), &q)
+            .into_iter()
+            .map(|(part, matched)| {
+                if matched {
+                    view! { <mark>{part}</mark> }.into_any()
+                } else {
+                    part.into_any()
+                }
+            })
+            .collect_view()
+    }
+}
+
+/// Splits `text` into parts, flagging the parts that match `q` case-insensitively.
+fn split_matches(text: &str, q: &str) -> Vec<(String, bool)> {
+    let q = q.trim().to_lowercase();
+    let chars: Vec<char> = text.chars().collect();
+    let lower: Vec<char> = chars
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+    let needle: Vec<char> = q.chars().collect();
+
+    let mut parts = vec![];
+    let mut start = 0;
+    let mut i = 0;
+    while !needle.is_empty() && i + needle.len() <= chars.len() {
+        if lower[i..i + needle.len()] == needle[..] {
+            if start < i {
+                parts.push((chars[start..i].iter().collect(), false));
+            }
+            parts.push((chars[i..i + needle.len()].iter().collect(), true));
+            i += needle.len();
+            start = i;
+        } else {
+            i += 1;
+        }
+    }
+    if start < chars.len() {
+        parts.push((chars[start..].iter().collect(), false));
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parts(parts: &[(&str, bool)]) -> Vec<(String, bool)> {
+        parts
+            .iter()
+            .map(|(part, matched)| (part.to_string(), *matched))
+            .collect()
+    }
+
+    #[test]
+    fn test_split_matches() {
+        assert_eq!(
+            split_matches("Bobby Bob", "bob"),
+            parts(&[("Bob", true), ("by ", false), ("Bob", true)])
+        );
+        assert_eq!(
+            split_matches("carol@none.co", "NONE"),
+            parts(&[("carol@", false), ("none", true), (".co", false)])
+        );
+        assert_eq!(split_matches("Carol", "xyz"), parts(&[("Carol", false)]));
+        assert_eq!(split_matches("Carol", " "), parts(&[("Carol", false)]));
+        assert!(split_matches("", "bob").is_empty());
+    }
+}
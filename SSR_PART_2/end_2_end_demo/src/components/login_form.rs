@@ -0,0 +1,91 @@
+use leptos::prelude::*;
+use leptos_router::components::A;
+use leptos_router::hooks::use_navigate;
+
+use crate::dto::auth_user::AuthUser;
+use crate::errors::user_error::UserError;
+use crate::server_fn::auth::{current_user, Login, Logout};
+
+pub type CurrentUserResource = Resource<Result<Option<AuthUser>, UserError>>;
+
+/// 提供登录、退出的 action 以及当前登录用户，登录或退出后自动刷新
+pub fn provide_auth() {
+    let login = ServerAction::<Login>::new();
+    let logout = ServerAction::<Logout>::new();
+    let user: CurrentUserResource = Resource::new(
+        move || (login.version().get(), logout.version().get()),
+        |_| current_user(),
+    );
+
+    provide_context(login);
+    provide_context(logout);
+    provide_context(user);
+}
+
+/// 导航栏中的当前用户：未登录时显示登录链接，已登录时显示用户名和退出按钮
+#[component]
+pub fn UserMenu() -> impl IntoView {
+    let logout = expect_context::<ServerAction<Logout>>();
+    let user = expect_context::<CurrentUserResource>();
+
+    view! {
+        <Transition fallback=|| ()>
+            {move || {
+                match user.get().and_then(|rt| rt.ok()).flatten() {
+                    Some(user) => {
+                        view! {
+                            <span>{user.fullname}</span>
+                            " "
+                            <button on:click=move |_| {
+                                logout.dispatch(Logout {});
+                            }>"退出"</button>
+                        }
+                            .into_any()
+                    }
+                    None => view! { <A href="/login">"登录"</A> }.into_any(),
+                }
+            }}
+        </Transition>
+    }
+}
+
+/// 登录页面，登录成功后回到首页
+#[component]
+pub fn LoginForm() -> impl IntoView {
+    let login = expect_context::<ServerAction<Login>>();
+    let navigate = use_navigate();
+
+    Effect::new(move || {
+        if let Some(Ok(_)) = login.value().get() {
+            login.value().set(None);
+            navigate("/", Default::default());
+        }
+    });
+
+    let error_msg = move || login.value().get().and_then(|rt| rt.err()).map(|e| e.to_string());
+
+    view! {
+        <div class="users-form">
+            <div class="form">
+                <Show when=move || error_msg().is_some()>
+                    <p class="error">{move || error_msg().unwrap_or_default()}</p>
+                </Show>
+                <ActionForm action=login>
+                    <div class="form_div">
+                        <label>
+                            "Email"
+                            <input type="email" name="email" autocomplete="username"/>
+                        </label>
+                    </div>
+                    <div class="form_div">
+                        <label>
+                            "Password"
+                            <input type="password" name="password" autocomplete="current-password"/>
+                        </label>
+                    </div>
+                    <input type="submit" value="登录"/>
+                </ActionForm>
+            </div>
+        </div>
+    }
+}
@@ -1,2 +1,11 @@
 pub mod users_lists_sig;
-pub mod users_form_sig;
\ No newline at end of file
+pub mod users_form_sig;
+pub mod change_password_form;
+pub mod workspace_list;
+pub mod login_form;
+pub mod users_trash;
+pub mod users_import;
+pub mod audit_log_panel;
+pub mod highlight;
+pub mod data_grid;
+pub mod users_columns;
@@ -0,0 +1,129 @@
+use leptos::prelude::*;
+
+use crate::components::data_grid::{CellEditor, GridColumn, GridRow};
+use crate::components::highlight::Highlight;
+use crate::dto::users_dto::UsersDto;
+use crate::dto::users_dto_sig::UsersDtoSig;
+use crate::dto::users_query::UsersSortColumn;
+use crate::dto::workspace_dto::WorkspaceDto;
+
+impl GridRow for UsersDtoSig {
+    type Key = i64;
+
+    fn key(&self) -> i64 {
+        self.id
+    }
+}
+
+/// Columns of the users list; `Fullname` and `Email` highlight the current search text, `WsId`
+/// is edited by choosing one of the given workspaces.
+#[derive(Debug, Clone, Copy)]
+pub enum UsersColumn {
+    Id,
+    Fullname(Signal<Option<String>>),
+    Gender,
+    Email(Signal<Option<String>>),
+    CreateAt,
+    WsId(Signal<Vec<WorkspaceDto>>),
+}
+
+impl UsersColumn {
+    /// All columns in display order, highlighting matches of `search`.
+    pub fn all(search: Signal<Option<String>>, workspaces: Signal<Vec<WorkspaceDto>>) -> Vec<Self> {
+        vec![
+            UsersColumn::Id,
+            UsersColumn::Fullname(search),
+            UsersColumn::Gender,
+            UsersColumn::Email(search),
+            UsersColumn::CreateAt,
+            UsersColumn::WsId(workspaces),
+        ]
+    }
+
+    /// Sets the field of `user` shown in this column to the edited `value`; `false` if the column
+    /// cannot be edited or `value` is not valid for it.
+    pub fn set_value(&self, user: &mut UsersDto, value: String) -> bool {
+        match self {
+            UsersColumn::Fullname(_) => user.fullname = value,
+            UsersColumn::Email(_) => user.email = value,
+            UsersColumn::WsId(_) => match value.parse() {
+                Ok(ws_id) => user.ws_id = ws_id,
+                Err(_) => return false,
+            },
+            UsersColumn::Id | UsersColumn::Gender | UsersColumn::CreateAt => return false,
+        }
+        true
+    }
+}
+
+impl GridColumn<UsersDtoSig> for UsersColumn {
+    type SortKey = UsersSortColumn;
+
+    fn title(&self) -> &'static str {
+        match self {
+            UsersColumn::Id => "ID",
+            UsersColumn::Fullname(_) => "fullname",
+            UsersColumn::Gender => "gender",
+            UsersColumn::Email(_) => "email",
+            UsersColumn::CreateAt => "create_at",
+            UsersColumn::WsId(_) => "ws_id",
+        }
+    }
+
+    fn sort_key(&self) -> Option<UsersSortColumn> {
+        match self {
+            UsersColumn::Id => Some(UsersSortColumn::Id),
+            UsersColumn::Fullname(_) => Some(UsersSortColumn::Fullname),
+            UsersColumn::Gender => None,
+            UsersColumn::Email(_) => Some(UsersSortColumn::Email),
+            UsersColumn::CreateAt => Some(UsersSortColumn::CreateAt),
+            UsersColumn::WsId(_) => Some(UsersSortColumn::WsId),
+        }
+    }
+
+    fn cell(&self, user: &UsersDtoSig) -> AnyView {
+        match *self {
+            UsersColumn::Id => user.id.into_any(),
+            UsersColumn::Fullname(q) => view! { <Highlight text=user.fullname q/> }.into_any(),
+            UsersColumn::Gender => {
+                let gender = user.gender;
+                (move || {
+                    gender
+                        .get()
+                        .map(|gender| gender.as_str())
+                        .unwrap_or_default()
+                })
+                .into_any()
+            }
+            UsersColumn::Email(q) => view! { <Highlight text=user.email q/> }.into_any(),
+            UsersColumn::CreateAt => user.create_at.clone().unwrap_or_default().into_any(),
+            UsersColumn::WsId(_) => {
+                let ws_id = user.ws_id;
+                (move || ws_id.get()).into_any()
+            }
+        }
+    }
+
+    fn editor(&self) -> Option<CellEditor> {
+        match *self {
+            UsersColumn::Fullname(_) | UsersColumn::Email(_) => Some(CellEditor::Text),
+            UsersColumn::WsId(workspaces) => Some(CellEditor::Select(Signal::derive(move || {
+                workspaces
+                    .get()
+                    .into_iter()
+                    .map(|ws| (ws.id.to_string(), ws.name))
+                    .collect()
+            }))),
+            UsersColumn::Id | UsersColumn::Gender | UsersColumn::CreateAt => None,
+        }
+    }
+
+    fn value(&self, user: &UsersDtoSig) -> String {
+        match self {
+            UsersColumn::Fullname(_) => user.fullname.get_untracked(),
+            UsersColumn::Email(_) => user.email.get_untracked(),
+            UsersColumn::WsId(_) => user.ws_id.get_untracked().to_string(),
+            UsersColumn::Id | UsersColumn::Gender | UsersColumn::CreateAt => String::new(),
+        }
+    }
+}
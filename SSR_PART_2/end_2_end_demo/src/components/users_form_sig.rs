@@ -1,12 +1,22 @@
-use leptos::{logging::log, prelude::*};
+use leptos::{ev::SubmitEvent, logging::log, prelude::*};
 
-use crate::{dto::users_dto_sig::UsersDtoSig, server_fn::user::AddOrUpdateUsers};
+use crate::{
+    components::change_password_form::ChangePasswordForm,
+    dto::{gender::Gender, users_dto::UsersDto, users_dto_sig::UsersDtoSig},
+    errors::{field_errors::FieldErrors, user_error::UserError},
+    server_fn::{user::AddOrUpdateUsers, workspace::get_workspaces},
+};
 
 #[component]
 pub fn UsersFormSig(users: UsersDtoSig, callback: Callback<UsersDtoSig>) -> impl IntoView {
     let submit = ServerAction::<AddOrUpdateUsers>::new();
 
     let (pre_submit_version, set_pre_submit_version) = signal(0);
+    let (error_msg, set_error_msg) = signal::<Option<String>>(None);
+    let (field_errors, set_field_errors) = signal(FieldErrors::new());
+    // 其他人已修改该用户时，服务端返回的最新数据
+    let (conflict, set_conflict) = signal::<Option<UsersDto>>(None);
+    let workspaces = Resource::new(|| (), |_| get_workspaces());
 
     // form 提交成功后，将最新 user 回传，用于更新 store 和页面展示.
     Effect::new(move || {
@@ -19,8 +29,28 @@ pub fn UsersFormSig(users: UsersDtoSig, callback: Callback<UsersDtoSig>) -> impl
 
         if current_submit_version.get() > pre_submit_version.get() {
             set_pre_submit_version.set(current_submit_version.get());
-            let submited_value = submit.value().get().unwrap().unwrap();
-            // let s = submited_value();
+            let submited_value = match submit.value().get().unwrap() {
+                Ok(value) => value,
+                // 字段校验失败，错误显示在对应的输入框旁边
+                Err(UserError::Validation(errors)) => {
+                    set_error_msg.set(None);
+                    set_field_errors.set(errors);
+                    return;
+                }
+                // 用户已被其他人修改，弹窗让用户选择使用最新数据还是保留自己的修改
+                Err(UserError::Conflict(current)) => {
+                    set_error_msg.set(None);
+                    set_conflict.set(Some(current));
+                    return;
+                }
+                Err(e) => {
+                    log!("submit failed: {:?}", e);
+                    set_error_msg.set(Some(e.to_string()));
+                    return;
+                }
+            };
+            set_error_msg.set(None);
+            set_field_errors.set(FieldErrors::new());
 
             log!("submited_value = {:?}", submited_value);
 
@@ -34,29 +64,77 @@ pub fn UsersFormSig(users: UsersDtoSig, callback: Callback<UsersDtoSig>) -> impl
             let pass_back_user = UsersDtoSig {
                 id: submited_value.id,
                 fullname: users.fullname, // 使用传入的 existing signal
+                gender: users.gender,     // 使用传入的 existing signal
                 email: users.email,       // 使用传入的 existing signal
                 create_at: created_time,
                 ws_id: users.ws_id, // 使用传入的 existing signal
+                version: users.version,
             };
 
             // 更新信号的值
             users.fullname.set(submited_value.fullname);
+            users.gender.set(submited_value.gender);
             users.email.set(submited_value.email);
             users.ws_id.set(submited_value.ws_id);
+            users.version.set(submited_value.version);
 
             callback.run(pass_back_user);
         }
     });
 
+    // 提交前在浏览器端执行与服务端相同的校验，不通过则不发送请求
+    let on_submit = move |ev: SubmitEvent| match AddOrUpdateUsers::from_event(&ev) {
+        Ok(data) => {
+            let errors = data.users_dto.validate();
+            if !errors.is_empty() {
+                ev.prevent_default();
+            }
+            set_field_errors.set(errors);
+        }
+        Err(e) => {
+            ev.prevent_default();
+            set_error_msg.set(Some(e.to_string()));
+        }
+    };
+    let field_error = move |field: &'static str| {
+        move || field_errors.with(|errors| errors.get(field).cloned())
+    };
+
+    // 使用服务端的最新数据覆盖表单
+    let use_current = move |_| {
+        if let Some(current) = conflict.get_untracked() {
+            users.fullname.set(current.fullname);
+            users.gender.set(current.gender);
+            users.email.set(current.email);
+            users.ws_id.set(current.ws_id);
+            users.version.set(current.version);
+        }
+        set_conflict.set(None);
+    };
+    // 保留自己的修改，再次提交时覆盖其他人的修改
+    let keep_mine = move |_| {
+        if let Some(current) = conflict.get_untracked() {
+            users.version.set(current.version);
+        }
+        set_conflict.set(None);
+    };
+
+    let user_id = users.id;
+    let is_new = user_id == -1;
+
     view! {
         <ActionForm
             action=submit
-            // on:submit:capture=on_submit
+            on:submit:capture=on_submit
         >
+            <Show when=move || error_msg.get().is_some()>
+                <p class="error">{move || error_msg.get().unwrap_or_default()}</p>
+            </Show>
             <div class="form_div">
             <label>"ID: "
                 <input type="number" name="users_dto[id]" readonly value=users.id/>
             </label>
+            <input type="hidden" name="users_dto[version]" prop:value=move || users.version.get()/>
             </div>
             <div class="form_div">
                 <label>
@@ -66,6 +144,28 @@ pub fn UsersFormSig(users: UsersDtoSig, callback: Callback<UsersDtoSig>) -> impl
                     prop:value = move || users.fullname.get()
                     />
                 </label>
+                <span class="error">{field_error("fullname")}</span>
+            </div>
+
+            <div class="form_div">
+                "Gender"
+                // 未指定时提交空值，服务端解析为 None
+                {std::iter::once(None)
+                    .chain(Gender::ALL.map(Some))
+                    .map(|gender| {
+                        let label = gender.map_or("Unspecified", |gender| gender.as_str());
+                        view! {
+                            <label>
+                                <input type="radio" name="users_dto[gender]"
+                                    value=gender.map_or("", |gender| gender.as_str())
+                                    prop:checked=move || users.gender.get() == gender
+                                    on:change=move |_| users.gender.set(gender)
+                                />
+                                {label}
+                            </label>
+                        }
+                    })
+                    .collect_view()}
             </div>
 
             <div class="form_div">
@@ -73,29 +173,92 @@ pub fn UsersFormSig(users: UsersDtoSig, callback: Callback<UsersDtoSig>) -> impl
                     "Email"
                     <input type="text" name="users_dto[email]" bind:value=users.email />
                 </label>
+                <span class="error">{field_error("email")}</span>
             </div>
 
             <div class="form_div">
                 <label>
-                    "分组ID"
+                    "分组"
                     <select name="users_dto[ws_id]"
                         on:change:target=move |ev| {
                             // set_ws_id.set(ev.target().value().parse().unwrap());
-                            users.ws_id.set(ev.target().value().parse().unwrap());
+                            // 无法解析的值不是有效的分组，忽略
+                            if let Ok(ws_id) = ev.target().value().parse() {
+                                users.ws_id.set(ws_id);
+                            }
                             }
-                            prop:value=move || users.ws_id
                     >
-                        <option>
-                            "0"
-                        </option>
-                        <option >
-                            "1"
-                        </option>
+                        <Suspense fallback=move || view! { <option>"加载中..."</option> }>
+                            {move || {
+                                workspaces
+                                    .get()
+                                    .and_then(|rt| rt.ok())
+                                    .unwrap_or_default()
+                                    .into_iter()
+                                    .map(|ws| {
+                                        view! {
+                                            <option
+                                                value=ws.id
+                                                selected=move || users.ws_id.get() == ws.id
+                                            >
+                                                {ws.name}
+                                            </option>
+                                        }
+                                    })
+                                    .collect_view()
+                            }}
+                        </Suspense>
                     </select>
                 </label>
+                <span class="error">{field_error("ws_id")}</span>
             </div>
 
+            // 只有新增用户时设置密码，已有用户通过下方的修改密码表单修改
+            <Show when=move || is_new>
+                <div class="form_div">
+                    <label>
+                        "Password"
+                        <input type="password" name="password" autocomplete="new-password"/>
+                    </label>
+                </div>
+                <div class="form_div">
+                    <label>
+                        "Confirm Password"
+                        <input type="password" name="password_confirm" autocomplete="new-password"/>
+                    </label>
+                </div>
+            </Show>
+
             <input type="submit"/>
         </ActionForm>
+
+        <Show when=move || conflict.with(Option::is_some)>
+            <div class="dialog">
+                <p>"该用户已被其他人修改，最新数据为："</p>
+                {move || {
+                    conflict
+                        .get()
+                        .map(|current| {
+                            view! {
+                                <table>
+                                    <tr><th>"Full Name"</th><td>{current.fullname}</td></tr>
+                                    <tr>
+                                        <th>"Gender"</th>
+                                        <td>{current.gender.map(|gender| gender.as_str()).unwrap_or_default()}</td>
+                                    </tr>
+                                    <tr><th>"Email"</th><td>{current.email}</td></tr>
+                                    <tr><th>"分组"</th><td>{current.ws_id}</td></tr>
+                                </table>
+                            }
+                        })
+                }}
+                <button on:click=use_current>"使用最新数据"</button>
+                <button on:click=keep_mine>"保留我的修改"</button>
+            </div>
+        </Show>
+
+        <Show when=move || !is_new>
+            <ChangePasswordForm user_id/>
+        </Show>
     }
 }
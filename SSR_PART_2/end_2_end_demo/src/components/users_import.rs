@@ -0,0 +1,87 @@
+use leptos::ev::SubmitEvent;
+use leptos::prelude::*;
+use leptos::wasm_bindgen::JsCast;
+use leptos::web_sys::{FormData, HtmlFormElement};
+
+use crate::dto::import_report::ImportOutcome;
+use crate::server_fn::user::import_users_csv;
+
+/// 上传 CSV 文件导入用户，导入完成后显示每一行的结果
+#[component]
+pub fn UsersImport(on_imported: Callback<()>) -> impl IntoView {
+    // FormData 只存在于浏览器，所以使用 local action
+    let import_action = Action::new_local(|data: &FormData| import_users_csv(data.clone().into()));
+
+    Effect::new(move || {
+        if let Some(Ok(_)) = import_action.value().get() {
+            on_imported.run(());
+        }
+    });
+
+    let on_submit = move |ev: SubmitEvent| {
+        ev.prevent_default();
+        let form = ev.target().unwrap().unchecked_into::<HtmlFormElement>();
+        let form_data = FormData::new_with_form(&form).unwrap();
+        import_action.dispatch_local(form_data);
+    };
+
+    view! {
+        <form class="toolbar" on:submit=on_submit>
+            <input type="file" name="file" accept=".csv,text/csv" required/>
+            <input
+                type="submit"
+                value="导入 CSV"
+                disabled=move || import_action.pending().get()
+            />
+        </form>
+        {move || {
+            import_action
+                .value()
+                .get()
+                .map(|rt| match rt {
+                    Ok(report) => {
+                        view! {
+                            <p>
+                                {format!(
+                                    "导入完成：成功 {} 行，失败 {} 行。",
+                                    report.imported(),
+                                    report.failed(),
+                                )}
+                            </p>
+                            <table>
+                                <thead>
+                                    <tr>
+                                        <th>"line"</th>
+                                        <th>"email"</th>
+                                        <th>"result"</th>
+                                    </tr>
+                                </thead>
+                                <tbody>
+                                    {report
+                                        .rows
+                                        .into_iter()
+                                        .map(|row| {
+                                            let (class, result) = match row.outcome {
+                                                ImportOutcome::Created(id) => ("", format!("新增 id = {}", id)),
+                                                ImportOutcome::Updated(id) => ("", format!("更新 id = {}", id)),
+                                                ImportOutcome::Failed(msg) => ("error", msg),
+                                            };
+                                            view! {
+                                                <tr>
+                                                    <td>{row.line}</td>
+                                                    <td>{row.email}</td>
+                                                    <td class=class>{result}</td>
+                                                </tr>
+                                            }
+                                        })
+                                        .collect_view()}
+                                </tbody>
+                            </table>
+                        }
+                            .into_any()
+                    }
+                    Err(e) => view! { <p class="error">{e.to_string()}</p> }.into_any(),
+                })
+        }}
+    }
+}
@@ -1,17 +1,86 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
 use leptos::logging::log;
 use leptos::prelude::*;
+use leptos_router::hooks::{use_location, use_navigate, use_query};
 
+use crate::components::audit_log_panel::AuditLogPanel;
+use crate::components::data_grid::{CellEdit, DataGrid, RowAction};
+use crate::components::users_columns::UsersColumn;
 use crate::components::users_form_sig::UsersFormSig;
+use crate::components::users_import::UsersImport;
+use crate::dto::bulk_result::BulkResult;
+#[cfg(feature = "hydrate")]
+use crate::dto::user_event::UserEvent;
+use crate::dto::users_dto::UsersDto;
 use crate::dto::users_dto_sig::UsersDtoSig;
-use crate::server_fn::user::delete_users;
-use crate::server_fn::user::get_users_sig;
+use crate::dto::users_query::{SortDirection, UsersQuery, UsersQueryParams, UsersSortColumn};
+use crate::errors::user_error::UserError;
+use crate::server_fn::auth::{Login, Logout};
+use crate::server_fn::user::{add_or_update_users, delete_users, delete_users_bulk};
+use crate::server_fn::user::{get_users_sig, search_users};
+use crate::server_fn::user::{move_users, restore_users};
+use crate::server_fn::workspace::get_workspaces;
+
+/// Time to wait after the last keystroke in the search box before searching.
+const SEARCH_DEBOUNCE: Duration = Duration::from_millis(300);
 
 #[component]
 pub fn UsersListSig() -> impl IntoView {
-    let async_users = Resource::new(|| (), |_| get_users_sig());
+    // 分页、排序和过滤条件保存在 URL 的 query string 中，刷新页面后保持当前视图
+    let query_params = use_query::<UsersQueryParams>();
+    let users_query = Memo::new(move |_| {
+        query_params
+            .get()
+            .map(UsersQuery::from)
+            .unwrap_or_default()
+    });
+
+    let navigate = use_navigate();
+    let location = use_location();
+    let update_query = Callback::new(move |query: UsersQuery| {
+        let path = location.pathname.get_untracked();
+        navigate(
+            &format!("{}{}", path, query.to_query_string()),
+            Default::default(),
+        );
+    });
+
+    // 能看到哪些用户取决于当前登录用户，登录或退出后重新加载
+    let login = expect_context::<ServerAction<Login>>();
+    let logout = expect_context::<ServerAction<Logout>>();
+    let async_users = Resource::new(
+        move || {
+            (
+                users_query.get(),
+                login.version().get(),
+                logout.version().get(),
+            )
+        },
+        |(query, _, _)| async move {
+            // 有搜索内容时按相似度排序
+            if query.q.is_some() {
+                search_users(query).await
+            } else {
+                get_users_sig(query).await
+            }
+        },
+    );
 
     let (users_list, set_users_list) = signal(vec![]);
+    let (total, set_total) = signal(0_u64);
+    let total_pages = move || {
+        total
+            .get()
+            .div_ceil(users_query.get().page_size())
+            .max(1)
+    };
     let (selected_line, set_selected_line) = signal::<Option<UsersDtoSig>>(None);
+    // 正在查看变更历史的用户 id
+    let (history_user_id, set_history_user_id) = signal::<Option<i64>>(None);
+    // 勾选的用户 id，用于批量删除和批量移动
+    let checked_ids = RwSignal::new(HashSet::<i64>::new());
 
     // 初始化数据集
     Effect::watch(
@@ -28,47 +97,297 @@ pub fn UsersListSig() -> impl IntoView {
 
                 // 只有当数据不同时才更新
                 if Some(users_date) != pre_users {
-                    if !users_date.is_empty() {
-                        set_users_list.set(users_date.to_owned());
-                    }
+                    set_users_list.set(users_date.items.to_owned());
+                    set_total.set(users_date.total);
+                    checked_ids.write().clear();
                 }
             }
         },
         true,
     );
 
+    // 其他浏览器增删改用户后，根据 /users/events 推送的事件增量更新当前列表
+    #[cfg(feature = "hydrate")]
+    let apply_user_event = move |event: UserEvent| match event {
+        UserEvent::Changed(users) => {
+            let mut has_new_users = false;
+            for user in users {
+                let row = users_list
+                    .with_untracked(|list| list.iter().find(|row| row.id == user.id).cloned());
+                match row {
+                    Some(row) => row.set_values(&user),
+                    None => has_new_users = true,
+                }
+            }
+            // 新用户所在的位置取决于过滤、排序和分页，重新加载当前页
+            if has_new_users {
+                async_users.refetch();
+            }
+        }
+        UserEvent::Removed(ids) => {
+            let before = users_list.with_untracked(Vec::len);
+            set_users_list.update(|list| list.retain(|user| !ids.contains(&user.id)));
+            let removed = before - users_list.with_untracked(Vec::len);
+            set_total.update(|total| *total = total.saturating_sub(removed as u64));
+            checked_ids.update(|checked| checked.retain(|id| !ids.contains(id)));
+        }
+        UserEvent::Resync => async_users.refetch(),
+    };
+
+    // 只在浏览器中订阅；登录或退出后重新订阅，因为能看到的用户变了
+    #[cfg(feature = "hydrate")]
+    {
+        let user_events = StoredValue::new_local(None);
+        Effect::new(move || {
+            login.version().track();
+            logout.version().track();
+            user_events.update_value(|current| {
+                if let Some((events, _)) = current.take() {
+                    close_user_events(events);
+                }
+                *current = subscribe_user_events(apply_user_event);
+            });
+        });
+        on_cleanup(move || {
+            user_events.try_update_value(|current| {
+                if let Some((events, _)) = current.take() {
+                    close_user_events(events);
+                }
+            });
+        });
+    }
+
+    // 点击表头排序：同一列切换方向，新列默认升序，并回到第一页
+    let sort_by = Callback::new(move |column: UsersSortColumn| {
+        let mut query = users_query.get_untracked();
+        query.sort_dir = if query.sort_by == column {
+            query.sort_dir.toggle()
+        } else {
+            SortDirection::Asc
+        };
+        query.sort_by = column;
+        query.page = 1;
+        update_query.run(query);
+    });
+    let sorted = Signal::derive(move || {
+        let query = users_query.get();
+        Some((query.sort_by, query.sort_dir))
+    });
+
+    // 搜索框：停止输入 SEARCH_DEBOUNCE 之后才更新 URL 中的 q 并搜索
+    let search_seq = StoredValue::new(0_u64);
+    let on_search_input = move |q: String| {
+        search_seq.update_value(|seq| *seq += 1);
+        let seq = search_seq.get_value();
+        set_timeout(
+            move || {
+                // 期间又有新的输入
+                if search_seq.get_value() != seq {
+                    return;
+                }
+                let q = Some(q.trim().to_string()).filter(|q| !q.is_empty());
+                let mut query = users_query.get_untracked();
+                if query.q != q {
+                    query.q = q;
+                    query.page = 1;
+                    update_query.run(query);
+                }
+            },
+            SEARCH_DEBOUNCE,
+        );
+    };
+    let search_q = Signal::derive(move || users_query.get().q);
+    let search_input = NodeRef::<leptos::html::Input>::new();
+    let search_value = move || {
+        let q = users_query.with(|query| query.q.clone().unwrap_or_default());
+        // 与搜索框内容只差首尾空白时不覆盖，否则会吃掉正在输入的空格
+        search_input
+            .get_untracked()
+            .map(|input| input.value())
+            .filter(|value| value.trim() == q)
+            .unwrap_or(q)
+    };
+
+    let go_to_page = move |page: u64| {
+        let mut query = users_query.get_untracked();
+        query.page = page;
+        update_query.run(query);
+    };
+
     // 删除用户 by id
+    let (error_msg, set_error_msg) = signal::<Option<String>>(None);
+    // 最近删除的用户 id，用于显示“撤销”提示
+    let (undo_ids, set_undo_ids) = signal::<Vec<i64>>(vec![]);
     let delete_user_action = Action::new(move |id: &i64| {
         let id = *id;
         async move {
             let rt = delete_users(id).await;
             match rt {
-                Ok(is_success) => {
-                    if is_success {
-                        leptos::logging::log!("delete is successful. now update the view");
+                Ok(()) => {
+                    leptos::logging::log!("delete is successful. now update the view");
+                    set_users_list.update(|list| {
+                        list.retain(|user| user.id != id);
+                    });
+                    set_total.update(|total| *total = total.saturating_sub(1));
+                    set_error_msg.set(None);
+                    set_undo_ids.set(vec![id]);
+
+                    leptos::logging::log!("view updated");
+                }
+                Err(e) => {
+                    leptos::logging::log!("delete not successful: {:?}", e);
+                    // 记录已经不存在，同步从列表中移除
+                    if let UserError::NotFound(_) = e {
                         set_users_list.update(|list| {
                             list.retain(|user| user.id != id);
                         });
+                    }
+                    set_error_msg.set(Some(e.to_string()));
+                }
+            }
+        }
+    });
 
-                        let remain_users: Vec<_> = users_list
-                            .get()
-                            .into_iter()
-                            .filter(|user| user.id != id)
-                            .collect();
-                        set_users_list.set(remain_users);
+    // 批量操作的结果：成功的 id 更新列表，失败的 id 汇总显示错误信息
+    let report_failures = move |results: &[BulkResult]| {
+        let failures: Vec<_> = results
+            .iter()
+            .filter_map(|rt| {
+                rt.error
+                    .as_ref()
+                    .map(|e| format!("id {}: {}", rt.id, e))
+            })
+            .collect();
+        set_error_msg.set((!failures.is_empty()).then(|| failures.join("; ")));
+    };
+    let succeeded_ids = |results: &[BulkResult]| -> HashSet<i64> {
+        results.iter().filter(|rt| rt.is_ok()).map(|rt| rt.id).collect()
+    };
 
-                        leptos::logging::log!("view updated");
-                    } else {
-                        leptos::logging::log!("delete not successful.");
+    // 批量删除
+    let delete_checked_action = Action::new(move |ids: &Vec<i64>| {
+        let ids = ids.clone();
+        async move {
+            match delete_users_bulk(ids).await {
+                Ok(results) => {
+                    let deleted = succeeded_ids(&results);
+                    set_users_list.update(|list| list.retain(|user| !deleted.contains(&user.id)));
+                    set_total.update(|total| *total = total.saturating_sub(deleted.len() as u64));
+                    checked_ids.update(|ids| ids.retain(|id| !deleted.contains(id)));
+                    if !deleted.is_empty() {
+                        set_undo_ids.set(deleted.into_iter().collect());
                     }
+                    report_failures(&results);
                 }
-                Err(_) => {
-                    leptos::logging::log!("error happened");
+                Err(e) => set_error_msg.set(Some(e.to_string())),
+            }
+        }
+    });
+
+    // 撤销删除：从回收站恢复后重新加载当前页
+    let undo_action = Action::new(move |ids: &Vec<i64>| {
+        let ids = ids.clone();
+        async move {
+            set_undo_ids.set(vec![]);
+            match restore_users(ids).await {
+                Ok(results) => {
+                    report_failures(&results);
+                    async_users.refetch();
                 }
+                Err(e) => set_error_msg.set(Some(e.to_string())),
             }
         }
     });
 
+    // “撤销”提示 10 秒后自动消失
+    Effect::watch(
+        move || undo_ids.get(),
+        move |ids, _, _| {
+            if ids.is_empty() {
+                return;
+            }
+            let ids = ids.clone();
+            set_timeout(
+                move || {
+                    if undo_ids.get_untracked() == ids {
+                        set_undo_ids.set(vec![]);
+                    }
+                },
+                Duration::from_secs(10),
+            );
+        },
+        false,
+    );
+
+    // 批量移动到其它分组
+    let workspaces = Resource::new(|| (), |_| get_workspaces());
+    let (move_to_ws_id, set_move_to_ws_id) = signal::<Option<i64>>(None);
+    let move_checked_action = Action::new(move |(ids, ws_id): &(Vec<i64>, i64)| {
+        let (ids, ws_id) = (ids.clone(), *ws_id);
+        async move {
+            match move_users(ids, ws_id).await {
+                Ok(results) => {
+                    let moved = succeeded_ids(&results);
+                    checked_ids.update(|ids| ids.retain(|id| !moved.contains(id)));
+                    report_failures(&results);
+                    // 移动后服务端增加了 version，且用户可能不再符合过滤条件，重新加载当前页
+                    if !moved.is_empty() {
+                        async_users.refetch();
+                    }
+                }
+                Err(e) => set_error_msg.set(Some(e.to_string())),
+            }
+        }
+    });
+
+    // 单元格内联编辑：先更新列表中的值，保存失败时恢复原值
+    let save_cell_action = Action::new(
+        move |(user, before, after): &(UsersDtoSig, UsersDto, UsersDto)| {
+            let (user, before, after) = (user.clone(), before.clone(), after.clone());
+            async move {
+                match add_or_update_users(after, None, None).await {
+                    Ok(saved) => {
+                        user.set_values(&saved);
+                        set_error_msg.set(None);
+                    }
+                    // 其他人已修改该用户，显示最新数据
+                    Err(UserError::Conflict(current)) => {
+                        user.set_values(&current);
+                        set_error_msg.set(Some("该用户已被其他人修改，已显示最新数据".to_string()));
+                    }
+                    Err(e) => {
+                        user.set_values(&before);
+                        set_error_msg.set(Some(match e {
+                            UserError::Validation(errors) => {
+                                errors.into_values().collect::<Vec<_>>().join("; ")
+                            }
+                            e => e.to_string(),
+                        }));
+                    }
+                }
+            }
+        },
+    );
+    let edit_cell = Callback::new(move |edit: CellEdit<UsersDtoSig, UsersColumn>| {
+        let before = edit.row.to_dto();
+        let mut after = before.clone();
+        if !edit.column.set_value(&mut after, edit.value) {
+            return;
+        }
+        // 与服务端相同的校验，不通过则不保存
+        let errors = after.validate();
+        if !errors.is_empty() {
+            set_error_msg.set(Some(errors.into_values().collect::<Vec<_>>().join("; ")));
+            return;
+        }
+        edit.row.set_values(&after);
+        save_cell_action.dispatch((edit.row, before, after));
+    });
+    let workspace_options =
+        Signal::derive(move || workspaces.get().and_then(|rt| rt.ok()).unwrap_or_default());
+
+    let checked_vec = move || checked_ids.with_untracked(|ids| ids.iter().copied().collect::<Vec<_>>());
+
     // 更新 或 新增
     let update_or_add_user = Callback::new(move |user_dto: UsersDtoSig| {
         // 新增
@@ -76,13 +395,19 @@ pub fn UsersListSig() -> impl IntoView {
             let new_user = UsersDtoSig::new(
                 user_dto.id,
                 user_dto.fullname,
+                user_dto.gender,
                 user_dto.email,
                 Some(created_time.chars().take(16).collect()),
                 user_dto.ws_id,
+                user_dto.version,
             );
 
             log!("new_user = {:?}", new_user.fullname.get());
-            set_users_list.write().push(new_user);
+            // 用户变更事件可能已经重新加载了列表
+            if users_list.with_untracked(|list| list.iter().all(|user| user.id != new_user.id)) {
+                set_users_list.write().push(new_user);
+                set_total.update(|total| *total += 1);
+            }
 
             set_selected_line.set(None);
         }
@@ -97,7 +422,8 @@ pub fn UsersListSig() -> impl IntoView {
                 .find(|user| user.id == user_dto.id);
 
             if let Some(update_user) = update_user_opt {
-                update_user.fullname.set(user_dto.fullname.get());
+                // 包括服务端返回的 version，否则下次保存会误报冲突
+                update_user.set_values(&user_dto.to_dto());
                 log!(
                     "updated: {}, target id is : {}",
                     user_dto.fullname.get(),
@@ -120,53 +446,153 @@ pub fn UsersListSig() -> impl IntoView {
                             <div>
                                 <button on:click= move |_| set_selected_line.set(Some(UsersDtoSig::default()))>"添加"</button>
                             </div>
-
-                            <table>
-                                <thead>
-                                    <tr>
-                                    <th>"ID"</th>
-                                    <th>"fullname"</th>
-                                    <th>"email"</th>
-                                    <th>"create_at"</th>
-                                    <th>"ws_id"</th>
-                                    <th>"操作"</th>
-                                    </tr>
-                                </thead>
-
-                                <tbody>
-
-                                    <ForEnumerate
-                                        each = move || users_list.get()
-                                        key = |user| user.id
-                                        children = move |_, user| {
-                                            let date = user.create_at.clone();
-                                            view! {
-                                                <tr on:click=move |_| {
-                                                    set_selected_line.set(Some(UsersDtoSig {
-                                                        id: user.id,
-                                                        fullname: user.fullname,
-                                                        email: user.email,
-                                                        create_at: date.clone(),
-                                                        ws_id: user.ws_id,
-                                                    }))
-                                                }>
-                                                                <th>{move || user.id}</th>
-                                                                <th>{move || user.fullname.get_untracked()}</th>
-                                                                <th>{move || user.email.get()}</th>
-                                                                <th>{move || user.create_at.clone().unwrap_or_default().clone()}</th>
-                                                                <th>{move || user.ws_id.get()}</th>
-                                                                <th>
-                                                                    <button on:click= move |ev| {
-                                                                        ev.stop_propagation();
-                                                                        delete_user_action.dispatch(user.id);
-                                                                    }>"删除"</button>
-                                                                </th>
-                                                </tr>
-                                            }
+                            <div class="filters">
+                                <input type="search" placeholder="搜索姓名或邮箱"
+                                    node_ref=search_input
+                                    // value 用于服务端渲染，prop:value 在前进 / 后退改变 q 时更新搜索框
+                                    value=users_query.get_untracked().q.unwrap_or_default()
+                                    prop:value=search_value
+                                    on:input:target=move |ev| on_search_input(ev.target().value())
+                                />
+                            </div>
+                            <div class="filters">
+                                <label>
+                                    "fullname: "
+                                    <input type="text"
+                                        prop:value=move || users_query.get().fullname.unwrap_or_default()
+                                        on:change:target=move |ev| {
+                                            let mut query = users_query.get_untracked();
+                                            query.fullname = Some(ev.target().value()).filter(|s| !s.is_empty());
+                                            query.page = 1;
+                                            update_query.run(query);
+                                        }
+                                    />
+                                </label>
+                                <label>
+                                    "email: "
+                                    <input type="text"
+                                        prop:value=move || users_query.get().email.unwrap_or_default()
+                                        on:change:target=move |ev| {
+                                            let mut query = users_query.get_untracked();
+                                            query.email = Some(ev.target().value()).filter(|s| !s.is_empty());
+                                            query.page = 1;
+                                            update_query.run(query);
                                         }
                                     />
-                                </tbody>
-                            </table>
+                                </label>
+                                <label>
+                                    "ws_id: "
+                                    <input type="number"
+                                        prop:value=move || users_query.get().ws_id.map(|id| id.to_string()).unwrap_or_default()
+                                        on:change:target=move |ev| {
+                                            let mut query = users_query.get_untracked();
+                                            query.ws_id = ev.target().value().parse().ok();
+                                            query.page = 1;
+                                            update_query.run(query);
+                                        }
+                                    />
+                                </label>
+                            </div>
+                            // 导出当前过滤和排序条件下的所有用户，rel="external" 让浏览器直接下载而不经过路由
+                            <div class="toolbar">
+                                <a rel="external" href=move || format!("/users/export.csv{}", users_query.get().to_query_string())>"导出 CSV"</a>
+                                <a rel="external" href=move || format!("/users/export.json{}", users_query.get().to_query_string())>"导出 JSON"</a>
+                            </div>
+                            <UsersImport on_imported=Callback::new(move |_| async_users.refetch())/>
+                            <div class="toolbar">
+                                <button
+                                    disabled=move || checked_ids.with(|ids| ids.is_empty())
+                                    on:click=move |_| {
+                                        delete_checked_action.dispatch(checked_vec());
+                                    }
+                                >"删除所选"</button>
+                                <select on:change:target=move |ev| {
+                                    set_move_to_ws_id.set(ev.target().value().parse().ok());
+                                }>
+                                    <option value="">"选择分组"</option>
+                                    <Suspense fallback=|| ()>
+                                        {move || {
+                                            workspaces
+                                                .get()
+                                                .and_then(|rt| rt.ok())
+                                                .unwrap_or_default()
+                                                .into_iter()
+                                                .map(|ws| view! { <option value=ws.id>{ws.name}</option> })
+                                                .collect_view()
+                                        }}
+                                    </Suspense>
+                                </select>
+                                <button
+                                    disabled=move || {
+                                        checked_ids.with(|ids| ids.is_empty()) || move_to_ws_id.get().is_none()
+                                    }
+                                    on:click=move |_| {
+                                        if let Some(ws_id) = move_to_ws_id.get_untracked() {
+                                            move_checked_action.dispatch((checked_vec(), ws_id));
+                                        }
+                                    }
+                                >"移动所选到分组"</button>
+                            </div>
+                            <Show when=move || !undo_ids.get().is_empty()>
+                                <div class="toast">
+                                    {move || format!("已删除 {} 个用户。", undo_ids.get().len())}
+                                    <button on:click=move |_| {
+                                        undo_action.dispatch(undo_ids.get_untracked());
+                                    }>"撤销"</button>
+                                    <button on:click=move |_| set_undo_ids.set(vec![])>"×"</button>
+                                </div>
+                            </Show>
+                            <Show when=move || error_msg.get().is_some()>
+                                <p class="error">{move || error_msg.get().unwrap_or_default()}</p>
+                            </Show>
+
+                            <DataGrid
+                                rows=users_list
+                                columns=UsersColumn::all(search_q, workspace_options)
+                                selection=checked_ids
+                                sorted
+                                on_sort=sort_by
+                                on_edit=Callback::new(move |user| set_selected_line.set(Some(user)))
+                                actions=vec![
+                                    RowAction::new("删除", move |id| {
+                                        delete_user_action.dispatch(id);
+                                    }),
+                                    RowAction::new("历史", move |id| set_history_user_id.set(Some(id))),
+                                ]
+                                on_cell_edit=edit_cell
+                            />
+
+                            <div class="pager">
+                                <button
+                                    disabled=move || users_query.get().page() <= 1
+                                    on:click=move |_| go_to_page(users_query.get_untracked().page() - 1)
+                                >"上一页"</button>
+                                <span>
+                                    {move || format!(
+                                        "第 {} / {} 页，共 {} 条",
+                                        users_query.get().page(),
+                                        total_pages(),
+                                        total.get()
+                                    )}
+                                </span>
+                                <button
+                                    disabled=move || users_query.get().page() >= total_pages()
+                                    on:click=move |_| go_to_page(users_query.get_untracked().page() + 1)
+                                >"下一页"</button>
+                                <select
+                                    prop:value=move || users_query.get().page_size().to_string()
+                                    on:change:target=move |ev| {
+                                        let mut query = users_query.get_untracked();
+                                        query.page_size = ev.target().value().parse().unwrap_or(UsersQuery::DEFAULT_PAGE_SIZE);
+                                        query.page = 1;
+                                        update_query.run(query);
+                                    }
+                                >
+                                    <option value="10">"10 条/页"</option>
+                                    <option value="20">"20 条/页"</option>
+                                    <option value="50">"50 条/页"</option>
+                                </select>
+                            </div>
                         </div>
         </Suspense>
 
@@ -190,5 +616,61 @@ pub fn UsersListSig() -> impl IntoView {
                 </div>
             </div>
         </Show>
+
+        {move || {
+            history_user_id
+                .get()
+                .map(|user_id| {
+                    view! {
+                        <AuditLogPanel
+                            user_id
+                            on_close=Callback::new(move |_| set_history_user_id.set(None))
+                        />
+                    }
+                })
+        }}
     }
 }
+
+/// An open `/users/events` stream together with the handler receiving its messages.
+#[cfg(feature = "hydrate")]
+type UserEventsSubscription = (
+    web_sys::EventSource,
+    leptos::wasm_bindgen::closure::Closure<dyn FnMut(web_sys::MessageEvent)>,
+);
+
+/// Opens the `/users/events` stream and passes every received [`UserEvent`] to `on_event`.
+///
+/// The returned message handler must be kept alive as long as the stream is open.
+#[cfg(feature = "hydrate")]
+fn subscribe_user_events(on_event: impl Fn(UserEvent) + 'static) -> Option<UserEventsSubscription> {
+    use leptos::wasm_bindgen::{closure::Closure, JsCast};
+    use web_sys::{EventSource, MessageEvent};
+
+    let on_message = Closure::<dyn FnMut(MessageEvent)>::new(move |ev: MessageEvent| {
+        let Some(data) = ev.data().as_string() else {
+            return;
+        };
+        match serde_json::from_str::<UserEvent>(&data) {
+            Ok(event) => on_event(event),
+            Err(e) => log!("invalid user event {:?}: {:?}", data, e),
+        }
+    });
+
+    match EventSource::new("/users/events") {
+        Ok(events) => {
+            events.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+            Some((events, on_message))
+        }
+        Err(e) => {
+            log!("failed to subscribe to user events: {:?}", e);
+            None
+        }
+    }
+}
+
+#[cfg(feature = "hydrate")]
+fn close_user_events(events: web_sys::EventSource) {
+    events.set_onmessage(None);
+    events.close();
+}
@@ -0,0 +1,105 @@
+use leptos::prelude::*;
+
+use crate::dto::bulk_result::BulkResult;
+use crate::errors::user_error::UserError;
+use crate::server_fn::user::{get_deleted_users, PurgeUsers, RestoreUsers};
+
+/// 回收站：列出已删除的用户，可以恢复或彻底删除
+#[component]
+pub fn UsersTrash() -> impl IntoView {
+    let restore_action = ServerAction::<RestoreUsers>::new();
+    let purge_action = ServerAction::<PurgeUsers>::new();
+
+    // 恢复或彻底删除后重新加载列表
+    let deleted_users = Resource::new(
+        move || (restore_action.version().get(), purge_action.version().get()),
+        |_| get_deleted_users(),
+    );
+
+    // 只显示失败的 id
+    let error_msg = move || {
+        let failures = |rt: Option<Result<Vec<BulkResult>, UserError>>| match rt {
+            Some(Ok(results)) => results
+                .into_iter()
+                .filter_map(|rt| rt.error.map(|e| format!("id {}: {}", rt.id, e)))
+                .collect::<Vec<_>>(),
+            Some(Err(e)) => vec![e.to_string()],
+            None => vec![],
+        };
+        let mut messages = failures(restore_action.value().get());
+        messages.extend(failures(purge_action.value().get()));
+        (!messages.is_empty()).then(|| messages.join("; "))
+    };
+
+    view! {
+        <div>
+            <Show when=move || error_msg().is_some()>
+                <p class="error">{move || error_msg().unwrap_or_default()}</p>
+            </Show>
+
+            <Suspense fallback=move || view! { <p>"加载中..."</p> }>
+                <table>
+                    <thead>
+                        <tr>
+                            <th>"ID"</th>
+                            <th>"fullname"</th>
+                            <th>"email"</th>
+                            <th>"ws_id"</th>
+                            <th>"deleted_at"</th>
+                            <th>"操作"</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {move || {
+                            deleted_users
+                                .get()
+                                .map(|rt| match rt {
+                                    Ok(list) if list.is_empty() => {
+                                        view! {
+                                            <tr>
+                                                <td colspan="6">"回收站是空的。"</td>
+                                            </tr>
+                                        }
+                                            .into_any()
+                                    }
+                                    Ok(list) => {
+                                        list.into_iter()
+                                            .map(|user| {
+                                                let id = user.id;
+                                                view! {
+                                                    <tr>
+                                                        <th>{user.id}</th>
+                                                        <th>{user.fullname}</th>
+                                                        <th>{user.email}</th>
+                                                        <th>{user.ws_id}</th>
+                                                        <th>{user.deleted_at}</th>
+                                                        <th>
+                                                            <button on:click=move |_| {
+                                                                restore_action.dispatch(RestoreUsers { ids: vec![id] });
+                                                            }>"恢复"</button>
+                                                            <button on:click=move |_| {
+                                                                purge_action.dispatch(PurgeUsers { ids: vec![id] });
+                                                            }>"彻底删除"</button>
+                                                        </th>
+                                                    </tr>
+                                                }
+                                            })
+                                            .collect_view()
+                                            .into_any()
+                                    }
+                                    Err(e) => {
+                                        view! {
+                                            <tr>
+                                                <td colspan="6" class="error">{e.to_string()}</td>
+                                            </tr>
+                                        }
+                                            .into_any()
+                                    }
+                                })
+                        }}
+                    </tbody>
+                </table>
+            </Suspense>
+        </div>
+    }
+}
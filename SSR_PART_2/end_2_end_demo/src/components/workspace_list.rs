@@ -0,0 +1,190 @@
+use leptos::prelude::*;
+use leptos::Params;
+use leptos_router::components::{Outlet, A};
+use leptos_router::hooks::{use_navigate, use_params};
+use leptos_router::params::Params;
+
+use crate::dto::workspace_dto::WorkspaceDto;
+use crate::errors::workspace_error::WorkspaceError;
+use crate::server_fn::workspace::{get_workspaces, AddOrUpdateWorkspace, DeleteWorkspace};
+
+type WorkspacesResource = Resource<Result<Vec<WorkspaceDto>, WorkspaceError>>;
+
+#[derive(Params, PartialEq)]
+struct WorkspaceParams {
+    id: Option<i64>,
+}
+
+/// 分组列表，详情通过 `<Outlet/>` 在列表下方展示
+#[component]
+pub fn WorkspaceList() -> impl IntoView {
+    let save_action = ServerAction::<AddOrUpdateWorkspace>::new();
+    let delete_action = ServerAction::<DeleteWorkspace>::new();
+
+    // 新增、更新或删除成功后重新加载列表
+    let workspaces = Resource::new(
+        move || (save_action.version().get(), delete_action.version().get()),
+        |_| get_workspaces(),
+    );
+
+    provide_context(workspaces);
+    provide_context(save_action);
+    provide_context(delete_action);
+
+    view! {
+        <div>
+            <div>
+                <A href="/workspaces/new">"添加"</A>
+            </div>
+
+            <Suspense fallback=move || view! { <p>"加载中..."</p> }>
+                <table>
+                    <thead>
+                        <tr>
+                            <th>"ID"</th>
+                            <th>"name"</th>
+                            <th>"owner_id"</th>
+                            <th>"create_at"</th>
+                            <th>"操作"</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {move || {
+                            workspaces
+                                .get()
+                                .map(|rt| match rt {
+                                    Ok(list) => {
+                                        list.into_iter()
+                                            .map(|ws| {
+                                                view! {
+                                                    <tr>
+                                                        <th>{ws.id}</th>
+                                                        <th>{ws.name}</th>
+                                                        <th>{ws.owner_id}</th>
+                                                        <th>{ws.create_at.unwrap_or_default()}</th>
+                                                        <th>
+                                                            <A href=format!("/workspaces/{}", ws.id)>"详细"</A>
+                                                        </th>
+                                                    </tr>
+                                                }
+                                            })
+                                            .collect_view()
+                                            .into_any()
+                                    }
+                                    Err(e) => {
+                                        view! {
+                                            <tr>
+                                                <td colspan="5" class="error">{e.to_string()}</td>
+                                            </tr>
+                                        }
+                                            .into_any()
+                                    }
+                                })
+                        }}
+                    </tbody>
+                </table>
+            </Suspense>
+
+            <Outlet />
+        </div>
+    }
+}
+
+/// 分组详情：`/workspaces/:id` 编辑已有分组，`/workspaces/new` 新增分组
+#[component]
+pub fn WorkspaceDetail() -> impl IntoView {
+    let workspaces = expect_context::<WorkspacesResource>();
+    let save_action = expect_context::<ServerAction<AddOrUpdateWorkspace>>();
+    let delete_action = expect_context::<ServerAction<DeleteWorkspace>>();
+    let navigate = use_navigate();
+
+    let params = use_params::<WorkspaceParams>();
+    let id = move || params.read().as_ref().ok().and_then(|params| params.id);
+
+    let workspace = move || match id() {
+        Some(id) => workspaces
+            .get()
+            .and_then(|rt| rt.ok())
+            .and_then(|list| list.into_iter().find(|ws| ws.id == id)),
+        None => Some(WorkspaceDto::default()),
+    };
+
+    // 新增成功后跳转到新分组的详情
+    Effect::new({
+        let navigate = navigate.clone();
+        move || {
+            if let (None, Some(Ok(ws))) = (id(), save_action.value().get()) {
+                save_action.value().set(None);
+                navigate(&format!("/workspaces/{}", ws.id), Default::default());
+            }
+        }
+    });
+
+    // 删除成功后回到列表
+    Effect::new(move || {
+        if let Some(Ok(())) = delete_action.value().get() {
+            delete_action.value().set(None);
+            navigate("/workspaces", Default::default());
+        }
+    });
+
+    let error_msg = move || {
+        let save_error = save_action.value().get().and_then(|rt| rt.err());
+        let delete_error = delete_action.value().get().and_then(|rt| rt.err());
+        save_error.or(delete_error).map(|e| e.to_string())
+    };
+    let name_error = move || match save_action.value().get() {
+        Some(Err(WorkspaceError::Validation(errors))) => errors.get("name").cloned(),
+        _ => None,
+    };
+
+    view! {
+        <Suspense fallback=move || view! { <p>"加载中..."</p> }>
+            <Show
+                when=move || workspace().is_some()
+                fallback=|| view! { <p>"分组不存在。"</p> }
+            >
+                {move || {
+                    let ws = workspace().unwrap_or_default();
+                    let ws_id = ws.id;
+                    view! {
+                        <div class="users-form">
+                            <div class="form">
+                                <Show when=move || error_msg().is_some()>
+                                    <p class="error">{move || error_msg().unwrap_or_default()}</p>
+                                </Show>
+                                <ActionForm action=save_action>
+                                    <div class="form_div">
+                                        <label>
+                                            "ID: "
+                                            <input type="number" name="workspace_dto[id]" readonly value=ws.id />
+                                        </label>
+                                    </div>
+                                    <div class="form_div">
+                                        <label>
+                                            "Name"
+                                            <input type="text" name="workspace_dto[name]" value=ws.name />
+                                        </label>
+                                        <span class="error">{name_error}</span>
+                                    </div>
+                                    <div class="form_div">
+                                        <label>
+                                            "Owner ID"
+                                            <input type="number" name="workspace_dto[owner_id]" readonly value=ws.owner_id />
+                                        </label>
+                                    </div>
+                                    <input type="submit" />
+                                </ActionForm>
+                                <Show when=move || ws_id != -1>
+                                    <button on:click=move |_| {
+                                        delete_action.dispatch(DeleteWorkspace { id: ws_id });
+                                    }>"删除"</button>
+                                </Show>
+                            </div>
+                        </div>
+                    }
+                }}
+            </Show>
+        </Suspense>
+    }
+}
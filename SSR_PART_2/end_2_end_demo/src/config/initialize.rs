@@ -2,7 +2,6 @@ use crate::config;
 use sea_orm::{
     ConnectOptions, ConnectionTrait, Database, DatabaseConnection, DbBackend, Statement,
 };
-use std::cmp::max;
 use std::time::Duration;
 
 use tracing_subscriber::layer::SubscriberExt;
@@ -16,9 +15,24 @@ pub async fn init_database() -> anyhow::Result<DatabaseConnection> {
     let mut options = ConnectOptions::new(db_config.database_url());
 
     let num_cpus = num_cpus::get() as u32;
+    let pool_size = pool_config.pool_size(num_cpus);
+    if let Some(computed) = pool_size.exceeded_ceiling {
+        tracing::warn!(
+            "Database pool size {} exceeds pool.max_connections_ceiling {}",
+            computed,
+            pool_config.max_connections_ceiling()
+        );
+    }
+    tracing::info!(
+        "Database pool sizing {:?} on {} CPUs: min_connections {}, max_connections {}",
+        pool_config.sizing(),
+        num_cpus,
+        pool_size.min_connections,
+        pool_size.max_connections
+    );
     options
-        .min_connections(max(num_cpus * 4, pool_config.min_connections()))
-        .max_connections(max(num_cpus * 8, pool_config.max_connections()))
+        .min_connections(pool_size.min_connections)
+        .max_connections(pool_size.max_connections)
         .connect_timeout(Duration::from_secs(pool_config.connect_timeout()))
         .acquire_timeout(Duration::from_secs(pool_config.read_timeout())) // read timeout
         .idle_timeout(Duration::from_secs(pool_config.idle_timeout()))
@@ -36,7 +50,11 @@ pub async fn init_database() -> anyhow::Result<DatabaseConnection> {
 
 pub fn init_logger() {
     tracing_subscriber::registry()
-        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")))
+        .with(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| {
+                EnvFilter::new(config::AppConfig::get().logging().filter())
+            }),
+        )
         .with(
             tracing_subscriber::fmt::layer()
                 .with_file(true)
@@ -46,9 +64,20 @@ pub fn init_logger() {
                 .with_target(false),
         )
         .init();
+
+    for warning in config::AppConfig::get().warnings() {
+        tracing::warn!("{}", warning);
+    }
 }
 
 async fn print_db_version(db: &DatabaseConnection) -> anyhow::Result<()> {
+    tracing::info!("Database version is: {}", db_version(db).await?);
+
+    Ok(())
+}
+
+/// Queries the version string of the database server.
+pub async fn db_version(db: &DatabaseConnection) -> anyhow::Result<String> {
     let version = db
         .query_one(Statement::from_string(
             DbBackend::Postgres,
@@ -56,10 +85,6 @@ async fn print_db_version(db: &DatabaseConnection) -> anyhow::Result<()> {
         ))
         .await?
         .ok_or_else(|| anyhow::anyhow!("Failed to get database version!"))?;
-    tracing::info!(
-        "Database version is: {}",
-        version.try_get_by_index::<String>(0)?
-    );
 
-    Ok(())
+    Ok(version.try_get_by_index::<String>(0)?)
 }
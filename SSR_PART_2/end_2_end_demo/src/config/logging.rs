@@ -0,0 +1,30 @@
+use serde::Deserialize;
+use tracing_subscriber::EnvFilter;
+
+/// Logging configuration.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LoggingConfig {
+    /// `tracing` filter directives, e.g. `info,end_2_end_demo=debug`.
+    /// The `RUST_LOG` environment variable takes precedence when set.
+    ///
+    /// Default: `info`
+    filter: Option<String>,
+}
+
+impl LoggingConfig {
+    pub fn filter(&self) -> &str {
+        self.filter.as_deref().unwrap_or("info")
+    }
+
+    /// Appends the problems of this section to `errors`.
+    pub(crate) fn validate(&self, errors: &mut Vec<String>) {
+        if let Err(e) = EnvFilter::try_new(self.filter()) {
+            errors.push(format!(
+                "logging.filter {:?} is not a valid filter: {}",
+                self.filter(),
+                e
+            ));
+        }
+    }
+}
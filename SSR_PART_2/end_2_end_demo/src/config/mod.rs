@@ -1,16 +1,24 @@
 use anyhow::{Context, Result};
 use config::{Config, FileFormat};
 // use config::{Config, FileFormat};
+use serde::de::{self, Deserializer, Visitor};
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::sync::LazyLock;
 
 use crate::config::database::{DbConfig, DbPoolConfig};
+use crate::config::logging::LoggingConfig;
+use crate::config::security::SecurityConfig;
+use crate::config::server::ServerConfig;
 
 #[cfg(feature = "ssr")]
 pub mod initialize;
 
 pub mod database;
+pub mod logging;
+pub mod security;
+pub mod server;
 
 /// Lazily initialized global application configuration.
 ///
@@ -19,9 +27,20 @@ static APP_CONFIG: LazyLock<AppConfig> =
     LazyLock::new(|| AppConfig::load().expect("Failed to load config"));
 
 #[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct AppConfig {
     database: DbConfig,
     pool: DbPoolConfig,
+    #[serde(default)]
+    server: ServerConfig,
+    #[serde(default)]
+    logging: LoggingConfig,
+    #[serde(default)]
+    security: SecurityConfig,
+    /// Problems of the `APP_` variables, logged once logging is set up, see
+    /// [`AppConfig::environment_vars`].
+    #[serde(skip)]
+    warnings: Vec<String>,
 }
 impl AppConfig {
     /// Loads configuration from multiple sources with the following priority:
@@ -30,35 +49,133 @@ impl AppConfig {
     ///    - Environment determined by `RUN_ENV` (default: `"dev"`)
     ///    - Example: `config/dev.yaml` for development
     ///    - Example: `config/prod.yaml` for production
-    /// 2. **Environment variables:** prefixed with `APP_`
+    /// 2. **Environment variables:** prefixed with `APP_`, see [`AppConfig::environment`]
     ///    - Override file values with higher priority
-    ///    - Example: `APP_SERVER_PORT=9090` overrides `server.port` in YAML
-    ///    - Nested keys use underscore separator: `APP_DATABASE_HOST`
+    ///    - Nested keys use a double underscore separator, as keys contain single ones:
+    ///      `APP_SERVER__REQUEST_TIMEOUT=30` overrides `server.request_timeout` in YAML
+    ///    - The single underscore form used before, e.g. `APP_DATABASE_HOST`, is deprecated but
+    ///      still read for the known keys, see [`AppConfig::environment_vars`]
+    ///    - Lists are comma separated: `APP_SECURITY__CORS_ALLOWED_ORIGINS=https://a.com,https://b.com`
+    ///    - Other `APP_` variables, e.g. `APP_NAME` set by the deploy platform, are ignored with a
+    ///      warning
+    ///
+    /// Unknown keys in the YAML file fail the loading.
     ///
     /// # Returns
     /// - `Ok(AppConfig)` on success
-    /// - `Err(anyhow::Error)` with context if loading or deserialization fails
+    /// - `Err(anyhow::Error)` with context if loading, deserialization or [`AppConfig::validate`]
+    ///   fails
     pub fn load() -> Result<Self> {
         // Determine the runtime environment, defaulting to "dev"
         let run_env = std::env::var("RUN_ENV").unwrap_or_else(|_| "dev".into());
+        let (vars, warnings) = Self::environment_vars(std::env::vars());
 
         // Build configuration from multiple sources
-        Config::builder()
+        let config = Config::builder()
             .add_source(
                 config::File::with_name(format!("config/{}.yaml", run_env).as_str())
                     .format(FileFormat::Yaml)
                     .required(false),
             )
             // Override with environment variables prefixed by `APP_`
-            .add_source(
-                config::Environment::with_prefix("APP")
-                    .try_parsing(true)
-                    .separator("_"),
-            )
+            .add_source(Self::environment().source(Some(vars)))
             .build()
-            .with_context(|| "Failed to load config file".to_string())?
+            .with_context(|| "Failed to load config file".to_string())?;
+
+        let mut app_config = Self::from_config(config)?;
+        app_config.warnings = warnings;
+        Ok(app_config)
+    }
+
+    /// The `APP_` environment variables overriding the YAML file.
+    fn environment() -> config::Environment {
+        config::Environment::with_prefix("APP")
+            .prefix_separator("_")
+            .separator("__")
+            .try_parsing(true)
+            .list_separator(",")
+            .with_list_parse_key("security.cors_allowed_origins")
+    }
+
+    /// The `APP_` variables of `vars` that set a known key, with warnings about the others.
+    ///
+    /// Keys in the single underscore form read before, e.g. `APP_DATABASE_HOST`, are renamed to
+    /// `APP_DATABASE__HOST` with a deprecation warning. Variables that are not a key, e.g. `APP_NAME`
+    /// injected by the deploy platform, are left out with a warning instead of failing on them.
+    fn environment_vars(
+        vars: impl IntoIterator<Item = (String, String)>,
+    ) -> (HashMap<String, String>, Vec<String>) {
+        let sections = [
+            ("database", struct_fields::<DbConfig>()),
+            ("pool", struct_fields::<DbPoolConfig>()),
+            ("server", struct_fields::<ServerConfig>()),
+            ("logging", struct_fields::<LoggingConfig>()),
+            ("security", struct_fields::<SecurityConfig>()),
+        ];
+        let is_key = |section: &str, field: &str| {
+            sections
+                .iter()
+                .any(|(name, fields)| *name == section && fields.contains(&field))
+        };
+
+        let mut known = HashMap::new();
+        let mut warnings = vec![];
+        for (name, value) in vars {
+            let Some(key) = name.strip_prefix("APP_") else {
+                continue;
+            };
+            let key = key.to_lowercase();
+            if let Some((section, field)) = key.split_once("__") {
+                if is_key(section, field) {
+                    known.insert(name, value);
+                    continue;
+                }
+            } else if let Some((section, field)) = sections.iter().find_map(|(section, _)| {
+                let field = key.strip_prefix(section)?.strip_prefix('_')?;
+                is_key(section, field).then_some((section, field))
+            }) {
+                let renamed = format!("APP_{}__{}", section, field).to_uppercase();
+                warnings.push(format!(
+                    "{name} is deprecated, nested keys are separated by a double underscore: \
+                     rename it to {renamed}"
+                ));
+                // 新写法同时存在时以新写法为准
+                known.entry(renamed).or_insert(value);
+                continue;
+            }
+            warnings.push(format!("{name} is not a config key and is ignored"));
+        }
+
+        (known, warnings)
+    }
+
+    /// Deserializes and validates the loaded `config`.
+    fn from_config(config: Config) -> Result<Self> {
+        let app_config: Self = config
             .try_deserialize()
-            .with_context(|| "Failed to deserialize config file".to_string())
+            .with_context(|| "Failed to deserialize config file".to_string())?;
+        app_config.validate()?;
+
+        Ok(app_config)
+    }
+
+    /// Checks the values that deserialize but cannot work together, reporting all of them at once.
+    pub fn validate(&self) -> Result<()> {
+        let mut errors = vec![];
+        self.pool.validate(&mut errors);
+        self.server.validate(&mut errors);
+        self.logging.validate(&mut errors);
+        self.security.validate(&mut errors);
+
+        if !errors.is_empty() {
+            anyhow::bail!("Invalid config:\n  - {}", errors.join("\n  - "));
+        }
+        Ok(())
+    }
+
+    /// Problems found while loading that did not prevent it, to be logged once logging is set up.
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
     }
 
     /// Returns a global, lazily initialized reference to the application configuration.
@@ -75,15 +192,231 @@ impl AppConfig {
     pub fn pool(&self) -> &DbPoolConfig {
         &self.pool
     }
+
+    /// Returns the HTTP server configuration.
+    pub fn server(&self) -> &ServerConfig {
+        &self.server
+    }
+
+    /// Returns the logging configuration.
+    pub fn logging(&self) -> &LoggingConfig {
+        &self.logging
+    }
+
+    /// Returns the security configuration.
+    pub fn security(&self) -> &SecurityConfig {
+        &self.security
+    }
+}
+
+/// The field names of the struct `T`, as its derived `Deserialize` reads them.
+fn struct_fields<'de, T: Deserialize<'de>>() -> &'static [&'static str] {
+    /// Records the fields passed to `deserialize_struct` and fails every deserialization.
+    struct Fields<'a>(&'a mut &'static [&'static str]);
+
+    impl<'de> Deserializer<'de> for Fields<'_> {
+        type Error = de::value::Error;
+
+        fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+            Err(de::Error::custom("not a struct"))
+        }
+
+        fn deserialize_struct<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            fields: &'static [&'static str],
+            _visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            *self.0 = fields;
+            Err(de::Error::custom("fields recorded"))
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+            option unit unit_struct newtype_struct seq tuple tuple_struct map enum identifier
+            ignored_any
+        }
+    }
+
+    let mut fields: &'static [&'static str] = &[];
+    let _ = T::deserialize(Fields(&mut fields));
+    fields
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn parse(yaml: &str) -> Result<AppConfig> {
+        let config = Config::builder()
+            .add_source(config::File::from_str(yaml, FileFormat::Yaml))
+            .build()?;
+        AppConfig::from_config(config)
+    }
+
     #[test]
     fn test_config() {
         let config = AppConfig::get();
         println!("{:?}", config);
     }
+
+    #[test]
+    fn test_validate() {
+        let config =
+            parse("database: { host: localhost }\npool: { min_connections: 1 }\n").unwrap();
+        assert_eq!(config.server().request_timeout(), 10);
+        assert_eq!(config.logging().filter(), "info");
+        assert!(config.security().cors_layer().is_none());
+
+        let error = parse(
+            "database: { host: localhost }\n\
+             pool: { min_connections: 10, max_connections: 5 }\n\
+             server: { shutdown_timeout: 0 }\n\
+             logging: { filter: \"info,demo=loud\" }\n\
+             security: { cors_allowed_origins: [\"example.com\"] }\n",
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(error.contains("pool.max_connections (5) must be >= pool.min_connections (10)"));
+        assert!(error.contains("server.shutdown_timeout must be greater than 0"));
+        assert!(error.contains("logging.filter"));
+        assert!(error.contains("\"example.com\" is not an origin"));
+    }
+
+    #[test]
+    fn test_environment_overrides() {
+        let env = [
+            ("APP_DATABASE__HOST", "db.internal"),
+            ("APP_POOL__MIN_CONNECTIONS", "2"),
+            ("APP_SERVER__REQUEST_TIMEOUT", "30"),
+            ("APP_SERVER__SHUTDOWN_TIMEOUT", "5"),
+            (
+                "APP_SECURITY__CORS_ALLOWED_ORIGINS",
+                "https://a.example.com,https://b.example.com",
+            ),
+        ];
+        let config = Config::builder()
+            .add_source(
+                AppConfig::environment().source(Some(
+                    env.iter()
+                        .map(|(key, value)| (key.to_string(), value.to_string()))
+                        .collect(),
+                )),
+            )
+            .build()
+            .unwrap();
+        let config = AppConfig::from_config(config).unwrap();
+
+        assert_eq!(config.database().host(), "db.internal");
+        assert_eq!(config.pool().min_connections(), 2);
+        assert_eq!(config.server().request_timeout(), 30);
+        assert_eq!(config.server().shutdown_timeout(), 5);
+        assert_eq!(
+            config.security().cors_allowed_origins(),
+            ["https://a.example.com", "https://b.example.com"]
+        );
+    }
+
+    #[test]
+    fn test_pool_size() {
+        let config =
+            parse("database: {}\npool: { min_connections: 10, max_connections: 20 }\n").unwrap();
+        let size = config.pool().pool_size(32);
+        assert_eq!((size.min_connections, size.max_connections), (10, 20));
+        assert_eq!(size.exceeded_ceiling, None);
+
+        let config = parse(
+            "database: {}\n\
+             pool: { sizing: auto, min_connections: 10, max_connections: 50 }\n",
+        )
+        .unwrap();
+        let size = config.pool().pool_size(1);
+        assert_eq!((size.min_connections, size.max_connections), (10, 10));
+        let size = config.pool().pool_size(4);
+        assert_eq!((size.min_connections, size.max_connections), (16, 32));
+        let size = config.pool().pool_size(32);
+        assert_eq!((size.min_connections, size.max_connections), (50, 50));
+        assert_eq!(size.exceeded_ceiling, None);
+
+        let config = parse(
+            "database: {}\n\
+             pool: { sizing: auto, min_connections: 10, max_connections: 200 }\n",
+        )
+        .unwrap();
+        let size = config.pool().pool_size(16);
+        assert_eq!((size.min_connections, size.max_connections), (64, 100));
+        assert_eq!(size.exceeded_ceiling, Some(128));
+
+        let config = parse(
+            "database: {}\n\
+             pool: { max_connections: 50, max_connections_ceiling: 40 }\n",
+        )
+        .unwrap();
+        let size = config.pool().pool_size(32);
+        assert_eq!(size.max_connections, 50);
+        assert_eq!(size.exceeded_ceiling, Some(50));
+
+        let error = parse("database: {}\npool: { max_connections_ceiling: 0 }\n")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("pool.max_connections_ceiling must be greater than 0"));
+    }
+
+    #[test]
+    fn test_environment_vars() {
+        let env = [
+            ("APP_DATABASE_PASSWORD", "secret"),
+            ("APP_DATABASE__HOST", "db.internal"),
+            ("APP_POOL_MIN_CONNECTIONS", "2"),
+            ("APP_POOL__MIN_CONNECTIONS", "3"),
+            ("APP_FOO", "bar"),
+            ("APP_NAME", "demo"),
+            ("APP_DATABASE__URL", "postgres://db.internal"),
+            ("PATH", "/usr/bin"),
+        ];
+        let (vars, warnings) = AppConfig::environment_vars(
+            env.iter()
+                .map(|(key, value)| (key.to_string(), value.to_string())),
+        );
+
+        let config = Config::builder()
+            .add_source(AppConfig::environment().source(Some(vars)))
+            .build()
+            .unwrap();
+        let config = AppConfig::from_config(config).unwrap();
+        assert_eq!(config.database().host(), "db.internal");
+        assert_eq!(config.database().password(), "secret");
+        assert_eq!(config.pool().min_connections(), 3);
+
+        let warnings = warnings.join("\n");
+        assert!(warnings.contains("APP_DATABASE_PASSWORD is deprecated"));
+        assert!(warnings.contains("rename it to APP_DATABASE__PASSWORD"));
+        assert!(warnings.contains("rename it to APP_POOL__MIN_CONNECTIONS"));
+        assert!(warnings.contains("APP_FOO is not a config key and is ignored"));
+        assert!(warnings.contains("APP_NAME is not a config key"));
+        assert!(warnings.contains("APP_DATABASE__URL is not a config key"));
+        assert!(!warnings.contains("APP_DATABASE__HOST"));
+        assert!(!warnings.contains("PATH"));
+        assert!(!warnings.contains("secret"));
+
+        assert!(parse("database: { hostname: db.internal }\npool: {}\n").is_err());
+    }
+
+    #[test]
+    fn test_struct_fields() {
+        assert_eq!(
+            struct_fields::<AppConfig>(),
+            ["database", "pool", "server", "logging", "security"]
+        );
+        assert!(struct_fields::<ServerConfig>().contains(&"request_timeout"));
+    }
+
+    #[test]
+    fn test_password_is_redacted() {
+        let config =
+            parse("database: { password: secret }\npool: { min_connections: 1 }\n").unwrap();
+        let debug = format!("{:?}", config.database());
+        assert!(!debug.contains("secret"));
+        assert!(debug.contains("***"));
+    }
 }
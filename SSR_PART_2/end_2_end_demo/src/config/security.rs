@@ -0,0 +1,105 @@
+use axum::http::header::CONTENT_TYPE;
+use axum::http::{HeaderValue, Method};
+use serde::Deserialize;
+use server_common::request_id::X_REQUEST_ID;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+/// Security configuration.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SecurityConfig {
+    /// Origins allowed to call the server from another site, e.g. `https://admin.example.com`,
+    /// or `["*"]` for any origin.
+    ///
+    /// Default: none, cross-origin requests are not allowed
+    cors_allowed_origins: Option<Vec<String>>,
+    /// Whether the session cookie is only sent over HTTPS; turn it off to log in over plain HTTP
+    /// in local development.
+    ///
+    /// Default: `true`
+    secure_cookies: Option<bool>,
+}
+
+impl SecurityConfig {
+    pub fn cors_allowed_origins(&self) -> &[String] {
+        self.cors_allowed_origins.as_deref().unwrap_or_default()
+    }
+
+    pub fn secure_cookies(&self) -> bool {
+        self.secure_cookies.unwrap_or(true)
+    }
+
+    /// CORS layer allowing [`SecurityConfig::cors_allowed_origins`], `None` when there are none.
+    ///
+    /// Session cookies are not sent with cross-origin requests, so those origins can only call
+    /// what does not need a login.
+    pub fn cors_layer(&self) -> Option<CorsLayer> {
+        let origins = self.cors_allowed_origins();
+        if origins.is_empty() {
+            return None;
+        }
+
+        let allow_origin = if origins.iter().any(|origin| origin == "*") {
+            AllowOrigin::any()
+        } else {
+            // 启动时已经校验过
+            AllowOrigin::list(
+                origins
+                    .iter()
+                    .filter_map(|origin| HeaderValue::from_str(origin).ok()),
+            )
+        };
+        Some(
+            CorsLayer::new()
+                .allow_origin(allow_origin)
+                .allow_methods([Method::GET, Method::POST])
+                .allow_headers([CONTENT_TYPE, X_REQUEST_ID.clone()])
+                .expose_headers([X_REQUEST_ID.clone()]),
+        )
+    }
+
+    /// Appends the problems of this section to `errors`.
+    pub(crate) fn validate(&self, errors: &mut Vec<String>) {
+        let origins = self.cors_allowed_origins();
+        if origins.len() > 1 && origins.iter().any(|origin| origin == "*") {
+            errors.push(
+                "security.cors_allowed_origins cannot combine \"*\" with other origins".to_string(),
+            );
+        }
+        for origin in origins.iter().filter(|origin| *origin != "*") {
+            if !is_valid_origin(origin) {
+                errors.push(format!(
+                    "security.cors_allowed_origins: {:?} is not an origin like \"https://example.com\"",
+                    origin
+                ));
+            }
+        }
+    }
+}
+
+/// Whether `origin` is a scheme and host with an optional port, without path or trailing slash.
+fn is_valid_origin(origin: &str) -> bool {
+    let Some(host) = origin
+        .strip_prefix("https://")
+        .or_else(|| origin.strip_prefix("http://"))
+    else {
+        return false;
+    };
+    !host.is_empty()
+        && !host.contains(['/', '?', '#', ' '])
+        && HeaderValue::from_str(origin).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid_origin() {
+        assert!(is_valid_origin("https://example.com"));
+        assert!(is_valid_origin("http://127.0.0.1:3000"));
+        assert!(!is_valid_origin("example.com"));
+        assert!(!is_valid_origin("https://example.com/"));
+        assert!(!is_valid_origin("https://"));
+    }
+}
@@ -0,0 +1,51 @@
+use serde::Deserialize;
+use std::net::SocketAddr;
+
+/// HTTP server configuration.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ServerConfig {
+    /// Address the server listens on, overriding `site-addr` of the Leptos options when set.
+    site_addr: Option<SocketAddr>,
+    /// Maximum time to answer a request before it fails with `408 Request Timeout` (seconds).
+    ///
+    /// Default: `10`
+    request_timeout: Option<u64>,
+    /// Time between a shutdown signal, from which `/readyz` fails, and closing the listener, so
+    /// the load balancer stops routing new requests here first (seconds).
+    ///
+    /// Default: `0`
+    pre_stop_delay: Option<u64>,
+    /// Maximum time to wait for in-flight requests after closing the listener (seconds).
+    ///
+    /// Default: `30`
+    shutdown_timeout: Option<u64>,
+}
+
+impl ServerConfig {
+    pub fn site_addr(&self) -> Option<SocketAddr> {
+        self.site_addr
+    }
+
+    pub fn request_timeout(&self) -> u64 {
+        self.request_timeout.unwrap_or(10)
+    }
+
+    pub fn pre_stop_delay(&self) -> u64 {
+        self.pre_stop_delay.unwrap_or(0)
+    }
+
+    pub fn shutdown_timeout(&self) -> u64 {
+        self.shutdown_timeout.unwrap_or(30)
+    }
+
+    /// Appends the problems of this section to `errors`.
+    pub(crate) fn validate(&self, errors: &mut Vec<String>) {
+        if self.request_timeout() == 0 {
+            errors.push("server.request_timeout must be greater than 0".to_string());
+        }
+        if self.shutdown_timeout() == 0 {
+            errors.push("server.shutdown_timeout must be greater than 0".to_string());
+        }
+    }
+}
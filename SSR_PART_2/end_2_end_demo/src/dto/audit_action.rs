@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+
+/// Kind of user mutation recorded in the audit log, stored in `audit_log.action`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuditAction {
+    Create,
+    Update,
+    Move,
+    Delete,
+    Restore,
+    Purge,
+    Import,
+}
+
+impl AuditAction {
+    pub const ALL: [AuditAction; 7] = [
+        AuditAction::Create,
+        AuditAction::Update,
+        AuditAction::Move,
+        AuditAction::Delete,
+        AuditAction::Restore,
+        AuditAction::Purge,
+        AuditAction::Import,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::Create => "create",
+            AuditAction::Update => "update",
+            AuditAction::Move => "move",
+            AuditAction::Delete => "delete",
+            AuditAction::Restore => "restore",
+            AuditAction::Purge => "purge",
+            AuditAction::Import => "import",
+        }
+    }
+
+    /// Parses the name returned by [`AuditAction::as_str`].
+    pub fn from_param(value: &str) -> Option<Self> {
+        AuditAction::ALL
+            .into_iter()
+            .find(|action| action.as_str() == value)
+    }
+
+    /// Label shown in the history panel.
+    pub fn label(&self) -> &'static str {
+        match self {
+            AuditAction::Create => "新增",
+            AuditAction::Update => "修改",
+            AuditAction::Move => "移动分组",
+            AuditAction::Delete => "删除",
+            AuditAction::Restore => "恢复",
+            AuditAction::Purge => "彻底删除",
+            AuditAction::Import => "导入",
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// One entry of the audit log of a user, see `get_audit_log`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AuditLogDto {
+    pub id: i64,
+    pub actor_id: i64,
+    /// Full name of the actor, `None` once the actor was purged
+    pub actor_name: Option<String>,
+    /// Name of an [`AuditAction`](crate::dto::audit_action::AuditAction)
+    pub action: String,
+    pub target_id: i64,
+    /// The user before the change, `None` when it was created
+    pub before: Option<Value>,
+    /// The user after the change, `None` when it was purged
+    pub after: Option<Value>,
+    pub create_at: String,
+}
+
+/// A field that differs between [`AuditLogDto::before`] and [`AuditLogDto::after`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: String,
+    pub after: String,
+}
+
+impl AuditLogDto {
+    /// Lists the fields whose value changed, in field name order.
+    pub fn changes(&self) -> Vec<FieldChange> {
+        let before = self.before.as_ref().and_then(Value::as_object);
+        let after = self.after.as_ref().and_then(Value::as_object);
+
+        let mut fields: Vec<&String> = before
+            .into_iter()
+            .chain(after)
+            .flat_map(|user| user.keys())
+            .collect();
+        fields.sort();
+        fields.dedup();
+
+        let value_of = |user: Option<&serde_json::Map<String, Value>>, field: &str| match user
+            .and_then(|user| user.get(field))
+        {
+            None | Some(Value::Null) => String::new(),
+            Some(Value::String(value)) => value.clone(),
+            Some(value) => value.to_string(),
+        };
+        fields
+            .into_iter()
+            .map(|field| FieldChange {
+                field: field.clone(),
+                before: value_of(before, field),
+                after: value_of(after, field),
+            })
+            .filter(|change| change.before != change.after)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn entry(before: Option<Value>, after: Option<Value>) -> AuditLogDto {
+        AuditLogDto {
+            id: 1,
+            actor_id: 0,
+            actor_name: None,
+            action: "update".to_string(),
+            target_id: 7,
+            before,
+            after,
+            create_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_changes() {
+        let changes = entry(
+            Some(json!({"fullname": "Bobby", "wsId": 0, "version": 1})),
+            Some(json!({"fullname": "Bob", "wsId": 0, "version": 2})),
+        )
+        .changes();
+        assert_eq!(
+            changes,
+            vec![
+                FieldChange {
+                    field: "fullname".to_string(),
+                    before: "Bobby".to_string(),
+                    after: "Bob".to_string(),
+                },
+                FieldChange {
+                    field: "version".to_string(),
+                    before: "1".to_string(),
+                    after: "2".to_string(),
+                },
+            ]
+        );
+
+        // a created user lists all its non-empty fields
+        let changes = entry(None, Some(json!({"fullname": "Bob", "gender": null}))).changes();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].field, "fullname");
+    }
+}
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+/// The logged-in user, as kept in the session and shown in the UI.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AuthUser {
+    pub id: i64,
+    pub fullname: String,
+    pub email: String,
+    pub ws_id: i64,
+}
+
+impl AuthUser {
+    pub fn new(id: i64, fullname: String, email: String, ws_id: i64) -> Self {
+        Self {
+            id,
+            fullname,
+            email,
+            ws_id,
+        }
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl From<crate::entity::users::Model> for AuthUser {
+    fn from(user: crate::entity::users::Model) -> Self {
+        Self::new(user.id, user.fullname, user.email, user.ws_id)
+    }
+}
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+use crate::errors::user_error::UserError;
+
+/// Outcome of one id of a bulk operation such as `delete_users_bulk`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BulkResult {
+    pub id: i64,
+    /// `None` when the operation succeeded for this id
+    pub error: Option<UserError>,
+}
+
+impl BulkResult {
+    pub fn ok(id: i64) -> Self {
+        Self { id, error: None }
+    }
+
+    pub fn err(id: i64, error: UserError) -> Self {
+        Self {
+            id,
+            error: Some(error),
+        }
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "ssr")]
+use crate::entity::sea_orm_active_enums;
+
+/// Client-side mirror of `entity::sea_orm_active_enums::Gender`, usable under `hydrate` without sea-orm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Gender {
+    Male,
+    Female,
+    Intersex,
+}
+
+impl Gender {
+    pub const ALL: [Gender; 3] = [Gender::Male, Gender::Female, Gender::Intersex];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Gender::Male => "Male",
+            Gender::Female => "Female",
+            Gender::Intersex => "Intersex",
+        }
+    }
+
+    /// Parses the name returned by [`Gender::as_str`], ignoring case.
+    pub fn from_param(value: &str) -> Option<Self> {
+        Gender::ALL
+            .into_iter()
+            .find(|gender| gender.as_str().eq_ignore_ascii_case(value))
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl From<sea_orm_active_enums::Gender> for Gender {
+    fn from(gender: sea_orm_active_enums::Gender) -> Self {
+        match gender {
+            sea_orm_active_enums::Gender::Male => Gender::Male,
+            sea_orm_active_enums::Gender::Female => Gender::Female,
+            sea_orm_active_enums::Gender::Intersex => Gender::Intersex,
+        }
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl From<Gender> for sea_orm_active_enums::Gender {
+    fn from(gender: Gender) -> Self {
+        match gender {
+            Gender::Male => sea_orm_active_enums::Gender::Male,
+            Gender::Female => sea_orm_active_enums::Gender::Female,
+            Gender::Intersex => sea_orm_active_enums::Gender::Intersex,
+        }
+    }
+}
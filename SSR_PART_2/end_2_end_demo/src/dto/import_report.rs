@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+/// What happened to one row of an imported CSV.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum ImportOutcome {
+    /// A new user was created with this id
+    Created(i64),
+    /// The user with this email already existed and was updated
+    Updated(i64),
+    /// The row was skipped
+    Failed(String),
+}
+
+/// Result of one CSV row, `line` counting from `1` for the header.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ImportRowResult {
+    pub line: u64,
+    pub email: String,
+    pub outcome: ImportOutcome,
+}
+
+/// Per-row report of `import_users_csv`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ImportReport {
+    pub rows: Vec<ImportRowResult>,
+}
+
+impl ImportReport {
+    /// Number of rows that were created or updated.
+    pub fn imported(&self) -> usize {
+        self.rows
+            .iter()
+            .filter(|row| !matches!(row.outcome, ImportOutcome::Failed(_)))
+            .count()
+    }
+
+    /// Number of rows that were skipped.
+    pub fn failed(&self) -> usize {
+        self.rows.len() - self.imported()
+    }
+}
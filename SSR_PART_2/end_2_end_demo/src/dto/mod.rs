@@ -1,2 +1,14 @@
+pub mod audit_action;
+pub mod audit_log_dto;
+pub mod auth_user;
+pub mod bulk_result;
+pub mod gender;
+pub mod import_report;
+pub mod page;
+pub mod trashed_user_dto;
+pub mod user_event;
+pub mod users_csv_row;
 pub mod users_dto;
-pub mod users_dto_sig;
\ No newline at end of file
+pub mod users_dto_sig;
+pub mod users_query;
+pub mod workspace_dto;
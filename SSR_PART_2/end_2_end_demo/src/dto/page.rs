@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+use crate::dto::users_query::UsersQuery;
+
+/// Highest page number served; larger pages are clamped to it, so `page * page_size` cannot
+/// overflow the query offset. Pages past the last row are empty anyway.
+pub const MAX_PAGE: u64 = 1_000_000;
+
+/// One page of a paginated query result.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Page<T> {
+    /// Rows of the current page
+    pub items: Vec<T>,
+    /// Total number of rows matching the query, across all pages
+    pub total: u64,
+    /// Current page, starting from `1`
+    pub page: u64,
+    /// Maximum number of rows per page
+    pub page_size: u64,
+}
+
+impl<T> Page<T> {
+    pub fn new(items: Vec<T>, total: u64, page: u64, page_size: u64) -> Self {
+        Self {
+            items,
+            total,
+            page,
+            page_size,
+        }
+    }
+}
+
+impl<T> Default for Page<T> {
+    fn default() -> Self {
+        Self {
+            items: vec![],
+            total: 0,
+            page: 1,
+            page_size: UsersQuery::DEFAULT_PAGE_SIZE,
+        }
+    }
+}
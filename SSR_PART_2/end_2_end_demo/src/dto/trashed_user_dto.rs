@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+/// A soft-deleted user, as listed in the trash.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TrashedUserDto {
+    pub id: i64,
+    pub fullname: String,
+    pub email: String,
+    pub ws_id: i64,
+    pub deleted_at: String,
+}
+
+#[cfg(feature = "ssr")]
+impl From<crate::entity::users::Model> for TrashedUserDto {
+    fn from(user: crate::entity::users::Model) -> Self {
+        Self {
+            id: user.id,
+            fullname: user.fullname,
+            email: user.email,
+            ws_id: user.ws_id,
+            deleted_at: user
+                .deleted_at
+                .map(|deleted_at| deleted_at.to_string().chars().take(16).collect())
+                .unwrap_or_default(),
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+use crate::dto::users_dto::UsersDto;
+
+/// Change to the users table, published by the user server functions and streamed to the open
+/// users lists by `/users/events`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum UserEvent {
+    /// Users were created, updated, moved or restored; holds their current values.
+    Changed(Vec<UsersDto>),
+    /// Users were deleted, or moved to a workspace the listening user cannot see.
+    Removed(Vec<i64>),
+    /// The listener missed some events and should reload the list.
+    Resync,
+}
+
+/// Change to the users table as published by the user server functions, with the workspaces of
+/// the users, so `/users/events` only tells each listener about the users it can see.
+#[derive(Debug, Clone, PartialEq)]
+pub enum UserChange {
+    /// Users were created, updated, moved or restored; holds their current values and the
+    /// workspace each was listed in before, `None` for created or restored users.
+    Changed(Vec<(UsersDto, Option<i64>)>),
+    /// Users were deleted; holds their ids and the workspace each was in.
+    Removed(Vec<(i64, i64)>),
+}
@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+
+use crate::dto::gender::Gender;
+use crate::dto::users_dto::UsersDto;
+
+/// One line of the users CSV export, and of the CSV import.
+///
+/// The import only reads `fullname`, `gender`, `email` and `ws_id`; `id` and `create_at` may be left
+/// out, since users are matched by email.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UsersCsvRow {
+    #[serde(default)]
+    pub id: Option<i64>,
+    pub fullname: String,
+    #[serde(default)]
+    pub gender: Option<String>,
+    pub email: String,
+    #[serde(default)]
+    pub create_at: Option<String>,
+    pub ws_id: i64,
+}
+
+impl From<UsersDto> for UsersCsvRow {
+    fn from(user: UsersDto) -> Self {
+        Self {
+            id: Some(user.id),
+            fullname: user.fullname,
+            gender: user.gender.map(|gender| gender.as_str().to_string()),
+            email: user.email,
+            create_at: user.create_at,
+            ws_id: user.ws_id,
+        }
+    }
+}
+
+impl UsersCsvRow {
+    /// Converts the row into a `UsersDto` to create, or an error message for an unknown gender.
+    pub fn into_users_dto(self) -> Result<UsersDto, String> {
+        let gender = match self.gender.as_deref().map(str::trim) {
+            None | Some("") => None,
+            Some(gender) => Some(
+                Gender::from_param(gender).ok_or_else(|| format!("unknown gender {}", gender))?,
+            ),
+        };
+
+        Ok(UsersDto::new(-1, self.fullname, gender, self.email, None, self.ws_id).normalized())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(gender: Option<&str>) -> UsersCsvRow {
+        UsersCsvRow {
+            id: None,
+            fullname: " Carol ".to_string(),
+            gender: gender.map(str::to_string),
+            email: "carol@none.co".to_string(),
+            create_at: None,
+            ws_id: 0,
+        }
+    }
+
+    #[test]
+    fn test_into_users_dto() {
+        let users_dto = row(Some("female")).into_users_dto().unwrap();
+        assert_eq!(users_dto.id, -1);
+        assert_eq!(users_dto.fullname, "Carol");
+        assert_eq!(users_dto.gender, Some(Gender::Female));
+
+        assert_eq!(row(Some("")).into_users_dto().unwrap().gender, None);
+        assert_eq!(row(None).into_users_dto().unwrap().gender, None);
+        assert!(row(Some("unknown")).into_users_dto().is_err());
+    }
+}
@@ -1,28 +1,33 @@
-use reactive_stores::Store;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Store)]
-pub struct UsersDtoDataStore {
-    #[store(key: String = |row| row.id.to_string())]
-    pub rows: Vec<UsersDto>,
-}
+use crate::dto::gender::Gender;
+use crate::errors::field_errors::FieldErrors;
 
-#[derive(Debug, Store, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct UsersDto {
     // pub key: String,
     pub id: i64,
     pub fullname: String,
-    // pub gender: Option<Gender>,
+    pub gender: Option<Gender>,
     pub email: String,
     pub create_at: Option<String>,
     pub ws_id: i64,
+    /// Row version the client last saw, `add_or_update_users` only updates the row if it still matches.
+    #[serde(default)]
+    pub version: i64,
 }
 
 impl UsersDto {
+    /// Maximum length of `fullname`, matching `users.fullname VARCHAR(64)`.
+    pub const FULLNAME_MAX_LEN: usize = 64;
+    /// Maximum length of `email`, matching `users.email VARCHAR(64)`.
+    pub const EMAIL_MAX_LEN: usize = 64;
+
     pub fn new(
         // key: String,
         id: i64,
         fullname: String,
+        gender: Option<Gender>,
         email: String,
         create_at: Option<String>,
         ws_id: i64,
@@ -31,12 +36,99 @@ impl UsersDto {
             // key,
             id,
             fullname,
+            gender,
             email,
             create_at,
             ws_id,
+            version: 0,
+        }
+    }
+
+    /// Sets the row version, see [`UsersDto::version`].
+    pub fn with_version(mut self, version: i64) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Trims the surrounding whitespace of `fullname` and `email`, as they are stored.
+    pub fn normalized(mut self) -> Self {
+        self.fullname = self.fullname.trim().to_string();
+        self.email = self.email.trim().to_string();
+        self
+    }
+
+    /// Checks the fields that can be validated without the database.
+    ///
+    /// Lengths are checked on the trimmed values, so the DTO must be [`normalized`](Self::normalized)
+    /// before it is persisted.
+    ///
+    /// Runs in the browser before the form is submitted and again in `add_or_update_users`,
+    /// which additionally checks that `ws_id` exists.
+    pub fn validate(&self) -> FieldErrors {
+        let mut errors = FieldErrors::new();
+
+        let fullname = self.fullname.trim();
+        if fullname.is_empty() {
+            errors.insert("fullname".to_string(), "full name is required".to_string());
+        } else if fullname.chars().count() > Self::FULLNAME_MAX_LEN {
+            errors.insert(
+                "fullname".to_string(),
+                format!(
+                    "full name must be at most {} characters",
+                    Self::FULLNAME_MAX_LEN
+                ),
+            );
+        }
+
+        let email = self.email.trim();
+        if email.is_empty() {
+            errors.insert("email".to_string(), "email is required".to_string());
+        } else if email.chars().count() > Self::EMAIL_MAX_LEN {
+            errors.insert(
+                "email".to_string(),
+                format!("email must be at most {} characters", Self::EMAIL_MAX_LEN),
+            );
+        } else if !is_valid_email(email) {
+            errors.insert("email".to_string(), "email is invalid".to_string());
+        }
+
+        if self.ws_id < 0 {
+            errors.insert("ws_id".to_string(), "please choose a workspace".to_string());
+        }
+
+        errors
+    }
+}
+
+/// A deliberately simple check: one `@`, a non-empty local part and a dotted domain, no whitespace.
+fn is_valid_email(email: &str) -> bool {
+    match email.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && !domain.contains('@')
+                && !email.chars().any(char::is_whitespace)
+                && domain.split('.').all(|label| !label.is_empty())
+                && domain.contains('.')
         }
+        None => false,
     }
-}   
+}
+
+#[cfg(feature = "ssr")]
+impl From<crate::entity::users::Model> for UsersDto {
+    fn from(user: crate::entity::users::Model) -> Self {
+        Self::new(
+            user.id,
+            user.fullname,
+            user.gender.map(Into::into),
+            user.email,
+            user.create_at
+                .map(|create_at| create_at.to_string().chars().take(16).collect()),
+            user.ws_id,
+        )
+        .with_version(user.version)
+    }
+}
 
 impl Default for UsersDto {
     fn default() -> Self {
@@ -44,9 +136,70 @@ impl Default for UsersDto {
             // key: String::from("-1"),
             id: -1,
             fullname: Default::default(),
+            gender: Default::default(),
             email: Default::default(),
             create_at: Default::default(),
             ws_id: Default::default(),
+            version: Default::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(fullname: &str, email: &str, ws_id: i64) -> UsersDto {
+        UsersDto::new(
+            -1,
+            fullname.to_string(),
+            None,
+            email.to_string(),
+            None,
+            ws_id,
+        )
+    }
+
+    #[test]
+    fn test_validate_valid_user() {
+        assert!(user("Carol", "carol@none.co", 0).validate().is_empty());
+    }
+
+    #[test]
+    fn test_normalized() {
+        let padded = format!("  {}  ", "a".repeat(64));
+        let users_dto = user(&padded, " carol@none.co\t", 0);
+        assert!(users_dto.validate().is_empty());
+
+        let users_dto = users_dto.normalized();
+        assert_eq!(users_dto.fullname, "a".repeat(64));
+        assert_eq!(users_dto.email, "carol@none.co");
+    }
+
+    #[test]
+    fn test_validate_invalid_fields() {
+        let errors = user(" ", "carol@", -1).validate();
+        assert_eq!(
+            errors.keys().collect::<Vec<_>>(),
+            vec!["email", "fullname", "ws_id"]
+        );
+
+        let errors = user(&"a".repeat(65), "carol@none.co", 0).validate();
+        assert!(errors.contains_key("fullname"));
+
+        for email in [
+            "carol",
+            "carol@none",
+            "@none.co",
+            "ca rol@none.co",
+            "carol@@none.co",
+            "carol@none..co",
+        ] {
+            assert!(
+                user("Carol", email, 0).validate().contains_key("email"),
+                "{}",
+                email
+            );
         }
     }
 }
@@ -1,15 +1,20 @@
-use leptos::prelude::RwSignal;
+use leptos::prelude::{GetUntracked, RwSignal, Set};
 use serde::{Deserialize, Serialize};
 
+use crate::dto::gender::Gender;
+use crate::dto::users_dto::UsersDto;
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct UsersDtoSig {
     // pub key: String,
     pub id: i64,
     pub fullname: RwSignal<String>,
-    // pub gender: Option<Gender>,
+    pub gender: RwSignal<Option<Gender>>,
     pub email: RwSignal<String>,
     pub create_at: Option<String>,
     pub ws_id: RwSignal<i64>,
+    /// Row version, bumped by every successful update of this user.
+    pub version: RwSignal<i64>,
 }
 
 impl UsersDtoSig {
@@ -17,19 +22,45 @@ impl UsersDtoSig {
         // key: String,
         id: i64,
         fullname: RwSignal<String>,
+        gender: RwSignal<Option<Gender>>,
         email: RwSignal<String>,
         create_at: Option<String>,
         ws_id: RwSignal<i64>,
+        version: RwSignal<i64>,
     ) -> Self {
         Self {
             id,
             fullname,
+            gender,
             email,
             create_at,
             ws_id,
+            version,
+        }
+    }
+
+    /// The current values, read without tracking.
+    pub fn to_dto(&self) -> UsersDto {
+        UsersDto {
+            id: self.id,
+            fullname: self.fullname.get_untracked(),
+            gender: self.gender.get_untracked(),
+            email: self.email.get_untracked(),
+            create_at: self.create_at.clone(),
+            ws_id: self.ws_id.get_untracked(),
+            version: self.version.get_untracked(),
         }
     }
-}   
+
+    /// Sets the signals to the values of `user`.
+    pub fn set_values(&self, user: &UsersDto) {
+        self.fullname.set(user.fullname.clone());
+        self.gender.set(user.gender);
+        self.email.set(user.email.clone());
+        self.ws_id.set(user.ws_id);
+        self.version.set(user.version);
+    }
+}
 
 impl Default for UsersDtoSig {
     fn default() -> Self {
@@ -37,9 +68,11 @@ impl Default for UsersDtoSig {
             // key: String::from("-1"),
             id: -1,
             fullname: Default::default(),
+            gender: Default::default(),
             email: Default::default(),
             create_at: Default::default(),
             ws_id: Default::default(),
+            version: Default::default(),
         }
     }
 }
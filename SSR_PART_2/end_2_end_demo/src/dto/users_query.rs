@@ -0,0 +1,196 @@
+use leptos::Params;
+use leptos_router::params::{Params, ParamsMap};
+use serde::{Deserialize, Serialize};
+
+use crate::dto::page::MAX_PAGE;
+
+/// Columns of the users list that can be sorted on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UsersSortColumn {
+    Id,
+    Fullname,
+    Email,
+    #[default]
+    CreateAt,
+    WsId,
+}
+
+impl UsersSortColumn {
+    /// Name used for this column in the URL query string.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UsersSortColumn::Id => "id",
+            UsersSortColumn::Fullname => "fullname",
+            UsersSortColumn::Email => "email",
+            UsersSortColumn::CreateAt => "create_at",
+            UsersSortColumn::WsId => "ws_id",
+        }
+    }
+
+    pub fn from_param(value: &str) -> Option<Self> {
+        match value {
+            "id" => Some(UsersSortColumn::Id),
+            "fullname" => Some(UsersSortColumn::Fullname),
+            "email" => Some(UsersSortColumn::Email),
+            "create_at" => Some(UsersSortColumn::CreateAt),
+            "ws_id" => Some(UsersSortColumn::WsId),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SortDirection {
+    Asc,
+    #[default]
+    Desc,
+}
+
+impl SortDirection {
+    /// Name used for this direction in the URL query string.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SortDirection::Asc => "asc",
+            SortDirection::Desc => "desc",
+        }
+    }
+
+    pub fn from_param(value: &str) -> Option<Self> {
+        match value {
+            "asc" => Some(SortDirection::Asc),
+            "desc" => Some(SortDirection::Desc),
+            _ => None,
+        }
+    }
+
+    pub fn toggle(&self) -> Self {
+        match self {
+            SortDirection::Asc => SortDirection::Desc,
+            SortDirection::Desc => SortDirection::Asc,
+        }
+    }
+}
+
+/// Paging, sorting and filtering options of the users list.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UsersQuery {
+    /// Page to load, starting from `1`
+    pub page: u64,
+    /// Number of rows per page
+    pub page_size: u64,
+    pub sort_by: UsersSortColumn,
+    pub sort_dir: SortDirection,
+    /// Case-sensitive substring filter on `fullname`
+    pub fullname: Option<String>,
+    /// Case-sensitive substring filter on `email`
+    pub email: Option<String>,
+    /// Exact filter on `ws_id`
+    pub ws_id: Option<i64>,
+    /// Search text matched against `fullname` and `email`, see `search_users`
+    pub q: Option<String>,
+}
+
+impl UsersQuery {
+    pub const DEFAULT_PAGE_SIZE: u64 = 10;
+    pub const MAX_PAGE_SIZE: u64 = 100;
+
+    /// Returns the page, clamped to `1..=MAX_PAGE`.
+    pub fn page(&self) -> u64 {
+        self.page.clamp(1, MAX_PAGE)
+    }
+
+    /// Returns the page size, clamped to `1..=MAX_PAGE_SIZE`.
+    pub fn page_size(&self) -> u64 {
+        self.page_size.clamp(1, Self::MAX_PAGE_SIZE)
+    }
+
+    /// Serializes the non-default parts of the query into a URL query string, e.g. `?page=2&sort=email`.
+    pub fn to_query_string(&self) -> String {
+        let default = UsersQuery::default();
+        let mut map = ParamsMap::new();
+
+        if self.page() != default.page {
+            map.insert("page", self.page().to_string());
+        }
+        if self.page_size() != default.page_size {
+            map.insert("page_size", self.page_size().to_string());
+        }
+        if self.sort_by != default.sort_by {
+            map.insert("sort", self.sort_by.as_str().to_string());
+        }
+        if self.sort_dir != default.sort_dir {
+            map.insert("dir", self.sort_dir.as_str().to_string());
+        }
+        if let Some(fullname) = self.fullname.as_ref().filter(|s| !s.is_empty()) {
+            map.insert("fullname", fullname.clone());
+        }
+        if let Some(email) = self.email.as_ref().filter(|s| !s.is_empty()) {
+            map.insert("email", email.clone());
+        }
+        if let Some(ws_id) = self.ws_id {
+            map.insert("ws_id", ws_id.to_string());
+        }
+        if let Some(q) = self.q.as_ref().filter(|s| !s.is_empty()) {
+            map.insert("q", q.clone());
+        }
+
+        map.to_query_string()
+    }
+}
+
+impl Default for UsersQuery {
+    fn default() -> Self {
+        Self {
+            page: 1,
+            page_size: Self::DEFAULT_PAGE_SIZE,
+            sort_by: Default::default(),
+            sort_dir: Default::default(),
+            fullname: None,
+            email: None,
+            ws_id: None,
+            q: None,
+        }
+    }
+}
+
+/// `UsersQuery` as it appears in the URL query string; missing or invalid values fall back to defaults.
+///
+/// Also deserializable, so plain Axum handlers such as the users export can read the same query string.
+#[derive(Params, Debug, Clone, PartialEq, Deserialize)]
+pub struct UsersQueryParams {
+    pub page: Option<u64>,
+    pub page_size: Option<u64>,
+    pub sort: Option<String>,
+    pub dir: Option<String>,
+    pub fullname: Option<String>,
+    pub email: Option<String>,
+    pub ws_id: Option<i64>,
+    pub q: Option<String>,
+}
+
+impl From<UsersQueryParams> for UsersQuery {
+    fn from(params: UsersQueryParams) -> Self {
+        let default = UsersQuery::default();
+        Self {
+            page: params.page.unwrap_or(default.page),
+            page_size: params.page_size.unwrap_or(default.page_size),
+            sort_by: params
+                .sort
+                .as_deref()
+                .and_then(UsersSortColumn::from_param)
+                .unwrap_or(default.sort_by),
+            sort_dir: params
+                .dir
+                .as_deref()
+                .and_then(SortDirection::from_param)
+                .unwrap_or(default.sort_dir),
+            fullname: params.fullname.filter(|s| !s.is_empty()),
+            email: params.email.filter(|s| !s.is_empty()),
+            ws_id: params.ws_id,
+            q: params
+                .q
+                .map(|q| q.trim().to_string())
+                .filter(|q| !q.is_empty()),
+        }
+    }
+}
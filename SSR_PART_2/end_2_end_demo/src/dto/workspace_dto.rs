@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+
+use crate::errors::field_errors::FieldErrors;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WorkspaceDto {
+    pub id: i64,
+    pub name: String,
+    pub owner_id: i64,
+    pub create_at: Option<String>,
+}
+
+impl WorkspaceDto {
+    /// Maximum length of `name`, matching `workspace.name VARCHAR(32)`.
+    pub const NAME_MAX_LEN: usize = 32;
+
+    pub fn new(id: i64, name: String, owner_id: i64, create_at: Option<String>) -> Self {
+        Self {
+            id,
+            name,
+            owner_id,
+            create_at,
+        }
+    }
+
+    /// Trims the surrounding whitespace of `name`, as it is stored.
+    pub fn normalized(mut self) -> Self {
+        self.name = self.name.trim().to_string();
+        self
+    }
+
+    /// Checks `name` on its trimmed value, so the DTO must be [`normalized`](Self::normalized)
+    /// before it is persisted.
+    pub fn validate(&self) -> FieldErrors {
+        let mut errors = FieldErrors::new();
+
+        let name = self.name.trim();
+        if name.is_empty() {
+            errors.insert("name".to_string(), "name is required".to_string());
+        } else if name.chars().count() > Self::NAME_MAX_LEN {
+            errors.insert(
+                "name".to_string(),
+                format!("name must be at most {} characters", Self::NAME_MAX_LEN),
+            );
+        }
+
+        errors
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl From<crate::entity::workspace::Model> for WorkspaceDto {
+    fn from(ws: crate::entity::workspace::Model) -> Self {
+        Self::new(
+            ws.id,
+            ws.name,
+            ws.owner_id,
+            ws.create_at
+                .map(|create_at| create_at.to_string().chars().take(16).collect()),
+        )
+    }
+}
+
+impl Default for WorkspaceDto {
+    fn default() -> Self {
+        Self {
+            id: -1,
+            name: Default::default(),
+            owner_id: Default::default(),
+            create_at: Default::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workspace(name: &str) -> WorkspaceDto {
+        WorkspaceDto::new(-1, name.to_string(), 0, None)
+    }
+
+    #[test]
+    fn test_validate_name() {
+        let padded = format!("  {}  ", "a".repeat(32));
+        assert!(workspace(&padded).validate().is_empty());
+        assert_eq!(workspace(&padded).normalized().name, "a".repeat(32));
+
+        for name in ["", "  ", &"a".repeat(33)] {
+            assert!(
+                workspace(name).validate().contains_key("name"),
+                "{:?}",
+                name
+            );
+        }
+    }
+}
@@ -3,6 +3,7 @@
 
 pub mod prelude;
 
+pub mod audit_log;
 pub mod sea_orm_active_enums;
 pub mod users;
 pub mod workspace;
@@ -1,4 +1,5 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.17
 
+pub use super::audit_log::Entity as AuditLog;
 pub use super::users::Entity as Users;
 pub use super::workspace::Entity as Workspace;
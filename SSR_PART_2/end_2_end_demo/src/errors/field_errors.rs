@@ -0,0 +1,6 @@
+use std::collections::BTreeMap;
+
+/// Validation messages keyed by the name of the invalid field, e.g. `"email" -> "email is invalid"`.
+///
+/// Empty when the input is valid.
+pub type FieldErrors = BTreeMap<String, String>;
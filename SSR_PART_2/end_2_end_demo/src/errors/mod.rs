@@ -0,0 +1,3 @@
+pub mod field_errors;
+pub mod user_error;
+pub mod workspace_error;
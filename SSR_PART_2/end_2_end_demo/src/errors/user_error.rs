@@ -0,0 +1,112 @@
+use leptos::server_fn::codec::JsonEncoding;
+use leptos::server_fn::error::{FromServerFnError, ServerFnErrorErr};
+use serde::{Deserialize, Serialize};
+
+use crate::dto::users_dto::UsersDto;
+use crate::errors::field_errors::FieldErrors;
+
+#[cfg(feature = "ssr")]
+use sea_orm::{DbErr, RuntimeErr};
+
+/// Errors returned by the user server functions.
+///
+/// Serialized to the client through leptos' custom server-fn error support,
+/// so the UI can tell the failure cases apart.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, thiserror::Error)]
+pub enum UserError {
+    /// No user exists with the given id.
+    #[error("user with id = {0} not found")]
+    NotFound(i64),
+    /// Another user already uses this email.
+    #[error("email is already used by another user")]
+    DuplicateEmail,
+    /// A user in the trash already uses this email; it has to be restored or purged first.
+    #[error("a deleted user uses this email, restore it from the trash first")]
+    EmailInTrash,
+    /// The referenced workspace (`ws_id`) does not exist.
+    #[error("workspace does not exist")]
+    WorkspaceNotFound,
+    /// Some fields of the submitted user are invalid, see [`UsersDto::validate`](crate::dto::users_dto::UsersDto::validate).
+    #[error("please correct the highlighted fields")]
+    Validation(FieldErrors),
+    /// A new user was submitted without a password.
+    #[error("password is required")]
+    PasswordRequired,
+    /// The password is shorter than the minimum length.
+    #[error("password must be at least {0} characters")]
+    PasswordTooShort(usize),
+    /// The password and its confirmation differ.
+    #[error("passwords do not match")]
+    PasswordMismatch,
+    /// The current password given to `change_password` is wrong.
+    #[error("current password is incorrect")]
+    WrongPassword,
+    /// Hashing the password failed.
+    #[error("failed to hash password: {0}")]
+    PasswordHash(String),
+    /// The request is not logged in.
+    #[error("please log in first")]
+    Unauthorized,
+    /// The logged-in user is not allowed to do this, e.g. a non-owner moving or deleting users.
+    #[error("you are not allowed to do this")]
+    Forbidden,
+    /// `login` was given an unknown email or a wrong password.
+    #[error("invalid email or password")]
+    InvalidCredentials,
+    /// The user was changed by someone else since the client loaded it; holds the current values.
+    #[error("user was changed by someone else")]
+    Conflict(UsersDto),
+    /// The user cannot be purged because it still owns a workspace.
+    #[error("user still owns a workspace")]
+    OwnsWorkspace,
+    /// The uploaded CSV file cannot be read.
+    #[error("invalid CSV file: {0}")]
+    InvalidCsv(String),
+    /// The database could not be reached.
+    #[error("database is unavailable, please try again later")]
+    DatabaseUnavailable,
+    /// Any other database error.
+    #[error("database error: {0}")]
+    Database(String),
+    /// Errors raised by the server-fn machinery itself (network, (de)serialization, ...).
+    #[error("server function error: {0}")]
+    ServerFnError(ServerFnErrorErr),
+}
+
+impl FromServerFnError for UserError {
+    type Encoder = JsonEncoding;
+
+    fn from_server_fn_error(value: ServerFnErrorErr) -> Self {
+        UserError::ServerFnError(value)
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl From<DbErr> for UserError {
+    /// Maps SeaORM errors to the distinct cases the UI cares about, by the name of the violated
+    /// constraint; violations of other constraints stay database errors.
+    fn from(err: DbErr) -> Self {
+        match violated_constraint(&err) {
+            Some("users_email_key") => return UserError::DuplicateEmail,
+            Some("users_ws_id_fkey") => return UserError::WorkspaceNotFound,
+            Some("workspace_owner_id_fkey") => return UserError::OwnsWorkspace,
+            _ => {}
+        }
+
+        match err {
+            DbErr::ConnectionAcquire(_) | DbErr::Conn(_) => UserError::DatabaseUnavailable,
+            e => UserError::Database(e.to_string()),
+        }
+    }
+}
+
+/// Returns the name of the constraint a failed statement violated, if any.
+#[cfg(feature = "ssr")]
+pub(crate) fn violated_constraint(err: &DbErr) -> Option<&str> {
+    match err {
+        DbErr::Exec(RuntimeErr::SqlxError(e)) | DbErr::Query(RuntimeErr::SqlxError(e)) => {
+            e.as_database_error()?.constraint()
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,88 @@
+use leptos::server_fn::codec::JsonEncoding;
+use leptos::server_fn::error::{FromServerFnError, ServerFnErrorErr};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::field_errors::FieldErrors;
+use crate::errors::user_error::UserError;
+
+#[cfg(feature = "ssr")]
+use crate::errors::user_error::violated_constraint;
+#[cfg(feature = "ssr")]
+use sea_orm::DbErr;
+
+/// Errors returned by the workspace server functions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, thiserror::Error)]
+pub enum WorkspaceError {
+    /// No workspace exists with the given id.
+    #[error("workspace with id = {0} not found")]
+    NotFound(i64),
+    /// Another workspace already uses this name.
+    #[error("workspace name is already used")]
+    DuplicateName,
+    /// Some fields of the submitted workspace are invalid, see [`WorkspaceDto::validate`](crate::dto::workspace_dto::WorkspaceDto::validate).
+    #[error("please correct the highlighted fields")]
+    Validation(FieldErrors),
+    /// The `owner_id` does not reference an existing user.
+    #[error("owner does not exist")]
+    OwnerNotFound,
+    /// The workspace still has users and cannot be deleted.
+    #[error("workspace still has users, move or delete them first")]
+    InUse,
+    /// The request is not logged in.
+    #[error("please log in first")]
+    Unauthorized,
+    /// The logged-in user is not allowed to do this, e.g. a non-owner renaming or deleting a workspace.
+    #[error("you are not allowed to do this")]
+    Forbidden,
+    /// The database could not be reached.
+    #[error("database is unavailable, please try again later")]
+    DatabaseUnavailable,
+    /// Any other database error.
+    #[error("database error: {0}")]
+    Database(String),
+    /// Errors raised by the server-fn machinery itself (network, (de)serialization, ...).
+    #[error("server function error: {0}")]
+    ServerFnError(ServerFnErrorErr),
+}
+
+impl FromServerFnError for WorkspaceError {
+    type Encoder = JsonEncoding;
+
+    fn from_server_fn_error(value: ServerFnErrorErr) -> Self {
+        WorkspaceError::ServerFnError(value)
+    }
+}
+
+impl From<UserError> for WorkspaceError {
+    /// Maps the errors of loading the caller's access, see `UserAccess::load`.
+    fn from(err: UserError) -> Self {
+        match err {
+            UserError::Unauthorized => WorkspaceError::Unauthorized,
+            UserError::Forbidden => WorkspaceError::Forbidden,
+            UserError::DatabaseUnavailable => WorkspaceError::DatabaseUnavailable,
+            UserError::Database(e) => WorkspaceError::Database(e),
+            UserError::ServerFnError(e) => WorkspaceError::ServerFnError(e),
+            e => WorkspaceError::Database(e.to_string()),
+        }
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl From<DbErr> for WorkspaceError {
+    /// Maps SeaORM errors to the distinct cases the UI cares about, by the name of the violated
+    /// constraint; violations of other constraints stay database errors.
+    fn from(err: DbErr) -> Self {
+        match violated_constraint(&err) {
+            Some("workspace_name_key") => return WorkspaceError::DuplicateName,
+            Some("workspace_owner_id_fkey") => return WorkspaceError::OwnerNotFound,
+            // users.ws_id 仍然引用要删除的 workspace
+            Some("users_ws_id_fkey") => return WorkspaceError::InUse,
+            _ => {}
+        }
+
+        match err {
+            DbErr::ConnectionAcquire(_) | DbErr::Conn(_) => WorkspaceError::DatabaseUnavailable,
+            e => WorkspaceError::Database(e.to_string()),
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use std::time::Duration;
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::Serialize;
+
+use crate::config::initialize::db_version;
+use crate::state::app_state::{AppState, PoolStats};
+
+/// How long `/readyz` waits for the database before reporting it unreachable.
+const READY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Routes `/healthz`, answering `200` as long as the process serves requests, and `/readyz`,
+/// answering `200` when the database answers within [`READY_TIMEOUT`] and the server is not
+/// shutting down, `503` otherwise.
+pub fn router(state: AppState) -> Router {
+    Router::new()
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
+        .with_state(state)
+}
+
+#[derive(Debug, Serialize)]
+struct Readiness {
+    ready: bool,
+    /// The server is shutting down and only finishes the in-flight requests.
+    draining: bool,
+    database: DatabaseStatus,
+    pool: Option<PoolStats>,
+    build: BuildInfo,
+}
+
+#[derive(Debug, Serialize)]
+struct DatabaseStatus {
+    reachable: bool,
+    version: Option<String>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct BuildInfo {
+    name: &'static str,
+    version: &'static str,
+}
+
+const BUILD_INFO: BuildInfo = BuildInfo {
+    name: env!("CARGO_PKG_NAME"),
+    version: env!("CARGO_PKG_VERSION"),
+};
+
+async fn healthz() -> Response {
+    Json(serde_json::json!({ "status": "ok" })).into_response()
+}
+
+async fn readyz(State(state): State<AppState>) -> Response {
+    let database = match tokio::time::timeout(READY_TIMEOUT, ping(&state)).await {
+        Ok(Ok(version)) => DatabaseStatus {
+            reachable: true,
+            version: Some(version),
+            error: None,
+        },
+        Ok(Err(e)) => unreachable_database(format!("{:#}", e)),
+        Err(_) => unreachable_database(format!("no answer within {:?}", READY_TIMEOUT)),
+    };
+
+    let draining = state.is_draining();
+    let readiness = Readiness {
+        ready: database.reachable && !draining,
+        draining,
+        database,
+        pool: state.pool_stats(),
+        build: BUILD_INFO,
+    };
+    let status = if readiness.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(readiness)).into_response()
+}
+
+/// Pings the database and returns its version.
+async fn ping(state: &AppState) -> anyhow::Result<String> {
+    state.db().ping().await?;
+    db_version(state.db()).await
+}
+
+fn unreachable_database(error: String) -> DatabaseStatus {
+    tracing::warn!("database is not ready: {}", error);
+    DatabaseStatus {
+        reachable: false,
+        version: None,
+        error: Some(error),
+    }
+}
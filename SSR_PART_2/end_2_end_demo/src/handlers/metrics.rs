@@ -0,0 +1,22 @@
+use axum::extract::State;
+use axum::response::Response;
+use axum::routing::get;
+use axum::Router;
+
+use crate::state::app_state::AppState;
+
+/// Routes `/metrics`, the [`Metrics`](server_common::metrics::Metrics) of the server in the
+/// Prometheus text format.
+pub fn router(state: AppState) -> Router {
+    Router::new()
+        .route("/metrics", get(metrics))
+        .with_state(state)
+}
+
+async fn metrics(State(state): State<AppState>) -> Response {
+    // 连接池的使用情况在抓取时读取
+    if let Some(pool) = state.pool_stats() {
+        state.metrics().set_db_pool(pool.in_use(), pool.idle);
+    }
+    state.metrics().response()
+}
@@ -0,0 +1,39 @@
+//! Plain Axum routes served next to the Leptos routes, for clients other than the Leptos app.
+
+pub mod health;
+pub mod metrics;
+pub mod user_events;
+pub mod users_export;
+
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+
+use crate::auth::session::session_token_from_headers;
+use crate::errors::user_error::UserError;
+use crate::server_fn::user::UserAccess;
+use crate::state::app_state::AppState;
+
+/// Loads the access rights of the user logged in with the session cookie of the request, or returns
+/// the error response to send instead.
+async fn authenticate(state: &AppState, headers: &HeaderMap) -> Result<UserAccess, Response> {
+    let Some(user) =
+        session_token_from_headers(headers).and_then(|token| state.sessions().get(&token))
+    else {
+        return Err((
+            StatusCode::UNAUTHORIZED,
+            UserError::Unauthorized.to_string(),
+        )
+            .into_response());
+    };
+
+    UserAccess::for_user(state.db(), user.id)
+        .await
+        .map_err(|e| {
+            tracing::error!("error loading user access: {:?}", e);
+            let status = match e {
+                UserError::Unauthorized => StatusCode::UNAUTHORIZED,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            (status, e.to_string()).into_response()
+        })
+}
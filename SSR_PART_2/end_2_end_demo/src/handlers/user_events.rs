@@ -0,0 +1,197 @@
+use std::convert::Infallible;
+use std::time::Duration;
+
+use axum::extract::State;
+use axum::http::HeaderMap;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use futures::stream::{self, StreamExt};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{broadcast, watch};
+use tokio::time::{interval_at, Instant, Interval};
+
+use crate::auth::session::session_token_from_headers;
+use crate::dto::user_event::{UserChange, UserEvent};
+use crate::handlers::authenticate;
+use crate::server_fn::user::UserAccess;
+use crate::state::app_state::AppState;
+
+/// How often an open stream checks that its session is still live and reloads which workspaces
+/// its user can see, when nothing else happens.
+const SESSION_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Routes `/users/events`, a server-sent events stream of the [`UserEvent`]s the logged-in user
+/// can see, each sent as JSON in the `data` field of a message.
+///
+/// The stream ends once the session is logged out or expires, or the user is deleted.
+pub fn router(state: AppState) -> Router {
+    Router::new()
+        .route("/users/events", get(user_events))
+        .with_state(state)
+}
+
+async fn user_events(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    let access = match authenticate(&state, &headers).await {
+        Ok(access) => access,
+        Err(response) => return response,
+    };
+    let token = session_token_from_headers(&headers).expect("authenticated with a session");
+
+    // 服务关闭时结束事件流，否则连接会一直保持到关闭超时
+    let draining = {
+        let state = state.clone();
+        async move { state.draining_started().await }
+    };
+    let listener = Listener {
+        receiver: state.subscribe_user_events(),
+        workspace_changes: state.subscribe_workspace_changes(),
+        session_check: interval_at(
+            Instant::now() + SESSION_CHECK_INTERVAL,
+            SESSION_CHECK_INTERVAL,
+        ),
+        state,
+        token,
+        access,
+    };
+    let events = stream::unfold(listener, |mut listener| async move {
+        let events = listener.next_events().await?;
+        Some((stream::iter(events), listener))
+    })
+    .flatten()
+    .map(|event| {
+        Ok::<_, Infallible>(
+            Event::default()
+                .json_data(&event)
+                .expect("UserEvent serializes to JSON"),
+        )
+    })
+    .take_until(draining);
+
+    Sse::new(events)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+/// An open `/users/events` stream and the access rights of its user.
+struct Listener {
+    state: AppState,
+    /// The session the stream was opened with.
+    token: String,
+    access: UserAccess,
+    receiver: broadcast::Receiver<UserChange>,
+    workspace_changes: watch::Receiver<()>,
+    session_check: Interval,
+}
+
+impl Listener {
+    /// Waits for the next events to send, `None` once the stream has to end.
+    async fn next_events(&mut self) -> Option<Vec<UserEvent>> {
+        loop {
+            tokio::select! {
+                biased;
+                changed = self.workspace_changes.changed() => {
+                    changed.ok()?;
+                    self.reload_access().await?;
+                }
+                received = self.receiver.recv() => {
+                    self.check_session()?;
+                    let change = match received {
+                        Ok(change) => change,
+                        Err(RecvError::Lagged(missed)) => {
+                            tracing::warn!("user events listener missed {:?} events", missed);
+                            self.reload_access().await?;
+                            return Some(vec![UserEvent::Resync]);
+                        }
+                        Err(RecvError::Closed) => return None,
+                    };
+                    // 用户自己被移动或删除时，能看到的 workspace 也变了
+                    if concerns(&change, self.access.user_id()) {
+                        self.reload_access().await?;
+                    }
+                    let events = visible_events(&self.access, change);
+                    if !events.is_empty() {
+                        return Some(events);
+                    }
+                }
+                _ = self.session_check.tick() => {
+                    self.check_session()?;
+                    self.reload_access().await?;
+                }
+            }
+        }
+    }
+
+    /// `None` once the session of the stream is logged out or expired.
+    fn check_session(&self) -> Option<()> {
+        let user = self.state.sessions().get(&self.token);
+        if user.is_none_or(|user| user.id != self.access.user_id()) {
+            tracing::info!(
+                "user events stream of user id = : {:?} ends with its session",
+                self.access.user_id()
+            );
+            return None;
+        }
+        Some(())
+    }
+
+    /// Reloads the workspaces the user can see, `None` if the user is gone or they cannot be read.
+    async fn reload_access(&mut self) -> Option<()> {
+        match UserAccess::for_user(self.state.db(), self.access.user_id()).await {
+            Ok(access) => {
+                self.access = access;
+                Some(())
+            }
+            Err(e) => {
+                tracing::error!("error reloading user access of user events stream: {:?}", e);
+                None
+            }
+        }
+    }
+}
+
+/// Whether `change` is about user `user_id`.
+fn concerns(change: &UserChange, user_id: i64) -> bool {
+    match change {
+        UserChange::Changed(users) => users.iter().any(|(user, _)| user.id == user_id),
+        UserChange::Removed(users) => users.iter().any(|&(id, _)| id == user_id),
+    }
+}
+
+/// Restricts `change` to the users `access` can see. Users moved to a workspace it cannot see are
+/// reported as removed, and only users it could see before are ever reported as removed.
+fn visible_events(access: &UserAccess, change: UserChange) -> Vec<UserEvent> {
+    // 之前看得到的用户才需要从列表中移除，其它分组的用户 id 不能发给监听者
+    let could_view = |ws_id: Option<i64>| ws_id.is_some_and(|ws_id| access.can_view(ws_id));
+
+    let mut visible = vec![];
+    let mut removed = vec![];
+    match change {
+        UserChange::Changed(users) => {
+            for (user, before) in users {
+                if access.can_view(user.ws_id) {
+                    visible.push(user);
+                } else if could_view(before) {
+                    removed.push(user.id);
+                }
+            }
+        }
+        UserChange::Removed(users) => {
+            removed = users
+                .into_iter()
+                .filter(|&(_, ws_id)| could_view(Some(ws_id)))
+                .map(|(id, _)| id)
+                .collect();
+        }
+    }
+
+    let mut events = vec![];
+    if !visible.is_empty() {
+        events.push(UserEvent::Changed(visible));
+    }
+    if !removed.is_empty() {
+        events.push(UserEvent::Removed(removed));
+    }
+    events
+}
@@ -0,0 +1,169 @@
+use axum::body::{Body, Bytes};
+use axum::extract::{Query, State};
+use axum::http::header::{CONTENT_DISPOSITION, CONTENT_TYPE};
+use axum::http::HeaderMap;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use futures::stream::{self, Stream, StreamExt};
+use sea_orm::{DbErr, PaginatorTrait};
+
+use crate::dto::users_csv_row::UsersCsvRow;
+use crate::dto::users_dto::UsersDto;
+use crate::dto::users_query::{UsersQuery, UsersQueryParams};
+use crate::handlers::authenticate;
+use crate::server_fn::user::select_users;
+use crate::state::app_state::AppState;
+
+/// Number of rows fetched from the database per chunk of the streamed response.
+const EXPORT_CHUNK_SIZE: u64 = 500;
+
+/// Routes `/users/export.csv` and `/users/export.json`.
+///
+/// Both take the filters and sort order of the users list in the query string, e.g.
+/// `/users/export.csv?email=none.co&sort=email`, and export every matching row the logged-in user
+/// can see; the paging parameters are ignored.
+pub fn router(state: AppState) -> Router {
+    Router::new()
+        .route("/users/export.csv", get(export_csv))
+        .route("/users/export.json", get(export_json))
+        .with_state(state)
+}
+
+async fn export_csv(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<UsersQueryParams>,
+) -> Response {
+    let header = "id,fullname,gender,email,create_at,ws_id\n";
+    let encode = |users: Vec<UsersDto>| {
+        let mut writer = csv::WriterBuilder::new()
+            .has_headers(false)
+            .from_writer(vec![]);
+        for user in users {
+            writer
+                .serialize(UsersCsvRow::from(user))
+                .map_err(io_error)?;
+        }
+        writer.into_inner().map_err(|e| io_error(e.into_error()))
+    };
+
+    let chunks = (Bytes::from_static(header.as_bytes()), Bytes::new());
+    export(
+        state,
+        &headers,
+        params,
+        "text/csv",
+        "users.csv",
+        chunks,
+        encode,
+    )
+    .await
+}
+
+async fn export_json(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<UsersQueryParams>,
+) -> Response {
+    // 逐块输出一个 JSON 数组，除第一个元素外都以 `,` 分隔
+    let mut first = true;
+    let encode = move |users: Vec<UsersDto>| {
+        let mut chunk = vec![];
+        for user in users {
+            if !std::mem::take(&mut first) {
+                chunk.push(b',');
+            }
+            serde_json::to_writer(&mut chunk, &user).map_err(io_error)?;
+        }
+        Ok(chunk)
+    };
+
+    let chunks = (Bytes::from_static(b"["), Bytes::from_static(b"]"));
+    export(
+        state,
+        &headers,
+        params,
+        "application/json",
+        "users.json",
+        chunks,
+        encode,
+    )
+    .await
+}
+
+/// Checks the session of the request, then streams the `(first, last)` chunks around the matching
+/// users, which are encoded by `encode` in chunks of [`EXPORT_CHUNK_SIZE`] rows.
+async fn export<F>(
+    state: AppState,
+    headers: &HeaderMap,
+    params: UsersQueryParams,
+    content_type: &'static str,
+    file_name: &'static str,
+    (first, last): (Bytes, Bytes),
+    encode: F,
+) -> Response
+where
+    F: FnMut(Vec<UsersDto>) -> std::io::Result<Vec<u8>> + Send + 'static,
+{
+    let access = match authenticate(&state, headers).await {
+        Ok(access) => access,
+        Err(response) => return response,
+    };
+
+    let query = UsersQuery::from(params);
+    let body = stream::once(async { Ok(first) })
+        .chain(users_chunks(state, query, access.visible_ws_ids(), encode))
+        .chain(stream::once(async { Ok(last) }));
+    (
+        [
+            (CONTENT_TYPE, content_type.to_string()),
+            (
+                CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", file_name),
+            ),
+        ],
+        Body::from_stream(body),
+    )
+        .into_response()
+}
+
+/// Pages through the users matching `query` and yields each non-empty page encoded by `encode`.
+fn users_chunks<F>(
+    state: AppState,
+    query: UsersQuery,
+    ws_ids: Vec<i64>,
+    encode: F,
+) -> impl Stream<Item = std::io::Result<Bytes>>
+where
+    F: FnMut(Vec<UsersDto>) -> std::io::Result<Vec<u8>> + Send + 'static,
+{
+    stream::try_unfold(
+        (state, query, ws_ids, encode, 0_u64),
+        |(state, query, ws_ids, mut encode, page)| async move {
+            let users = select_users(&query, &ws_ids)
+                .paginate(state.db(), EXPORT_CHUNK_SIZE)
+                .fetch_page(page)
+                .await
+                .map_err(db_error)?;
+            if users.is_empty() {
+                return Ok(None);
+            }
+
+            let chunk = encode(users.into_iter().map(UsersDto::from).collect())?;
+            Ok(Some((
+                Bytes::from(chunk),
+                (state, query, ws_ids, encode, page + 1),
+            )))
+        },
+    )
+}
+
+fn io_error(e: impl std::error::Error + Send + Sync + 'static) -> std::io::Error {
+    std::io::Error::other(e)
+}
+
+fn db_error(e: DbErr) -> std::io::Error {
+    tracing::error!("error exporting users: {:?}", e);
+    io_error(e)
+}
@@ -1,3 +1,5 @@
+#![recursion_limit = "256"]
+
 pub mod app;
 
 #[cfg(feature = "ssr")] // 整个 backend 模块只在server使用
@@ -9,7 +11,20 @@ pub mod config;
 #[cfg(feature = "ssr")] // 整个 backend 模块只在server使用
 pub mod state;
 
+#[cfg(feature = "ssr")] // 整个 backend 模块只在server使用
+pub mod migration;
+
+#[cfg(feature = "ssr")] // 整个 backend 模块只在server使用
+pub mod auth;
+
+#[cfg(feature = "ssr")] // 整个 backend 模块只在server使用
+pub mod handlers;
+
+#[cfg(feature = "ssr")] // 整个 backend 模块只在server使用
+pub mod middleware;
+
 pub mod dto;
+pub mod errors;
 pub mod server_fn;
 pub mod components;
 
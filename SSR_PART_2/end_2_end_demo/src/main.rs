@@ -1,17 +1,32 @@
+#![recursion_limit = "256"]
+
 use end_2_end_demo::state::app_state::AppState;
 
 #[cfg(feature = "ssr")]
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    use axum::http::StatusCode;
     use axum::Router;
+    use std::time::Duration;
+    use tower_http::timeout::TimeoutLayer;
     use leptos::logging::log;
     use leptos::prelude::*;
     use leptos_axum::{generate_route_list, LeptosRoutes};
     use end_2_end_demo::app::*;
+    use end_2_end_demo::auth::session::provide_current_user;
+    use server_common::request_id::{provide_request_id, set_request_id};
+    use end_2_end_demo::middleware::tracing_request::trace_request;
+
+    // 配置在第一次使用时加载并校验，无效时启动失败并列出所有错误
+    use end_2_end_demo::config::AppConfig;
+    let app_config = AppConfig::get();
 
     let conf = get_configuration(None).unwrap();
-    let addr = conf.leptos_options.site_addr;
-    let leptos_options = conf.leptos_options;
+    let mut leptos_options = conf.leptos_options;
+    if let Some(site_addr) = app_config.server().site_addr() {
+        leptos_options.site_addr = site_addr;
+    }
+    let addr = leptos_options.site_addr;
     // Generate the list of routes in your Leptos App
     let routes = generate_route_list(App);
 
@@ -23,29 +38,90 @@ async fn main() -> anyhow::Result<()> {
     // Initialize database connection
     let db_connection = initialize::init_database().await?;
 
+    // `migrate <up|down|status> [steps]` 子命令：只执行数据库迁移，不启动服务
+    use end_2_end_demo::migration::{self, Migrator, MigratorTrait};
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("migrate") {
+        return migration::run_command(&db_connection, &args[1..]).await;
+    }
+
+    // `set-password <email> <password>` 子命令：设置用户密码，用于初始化第一个可登录的账号
+    if args.first().map(String::as_str) == Some("set-password") {
+        return end_2_end_demo::auth::command::run_set_password(&db_connection, &args[1..]).await;
+    }
+
+    if app_config.database().auto_migrate() {
+        tracing::info!("Applying pending database migrations......");
+        Migrator::up(&db_connection, None).await?;
+    }
+
     // Create application state with database connection
     let app_state = AppState::new(db_connection);
+    // 定期清理过期的 session，避免内存中的 session 无限增长
+    app_state.sessions().spawn_sweep();
+    let shutdown_state = app_state.clone();
+    let export_routes = end_2_end_demo::handlers::users_export::router(app_state.clone());
+    let event_routes = end_2_end_demo::handlers::user_events::router(app_state.clone());
+    let metrics_routes = end_2_end_demo::handlers::metrics::router(app_state.clone());
+    let health_routes = end_2_end_demo::handlers::health::router(app_state.clone());
+    let metrics_layer = app_state.metrics().layer();
 
     let app = Router::new()
         .leptos_routes_with_context(
             &leptos_options,
             routes,
-            move || provide_context(app_state.clone()), // 将 AppState 放入到全局管理上下文中去
+            move || {
+                provide_context(app_state.clone()); // 将 AppState 放入到全局管理上下文中去
+                provide_current_user(&app_state); // 根据 session cookie 解析当前登录用户
+                provide_request_id(); // 让 server fn 可以拿到当前请求的 X-Request-Id
+            },
             {
                 let leptos_options = leptos_options.clone();
                 move || shell(leptos_options.clone())
             },
         )
         .fallback(leptos_axum::file_and_error_handler(shell))
-        .with_state(leptos_options);
+        .with_state(leptos_options)
+        // 用户导出：/users/export.csv 和 /users/export.json
+        .merge(export_routes)
+        // 用户变更事件：/users/events (server-sent events)
+        .merge(event_routes)
+        // Prometheus 指标：/metrics
+        .merge(metrics_routes)
+        // 存活和就绪检查：/healthz 和 /readyz
+        .merge(health_routes)
+        // 请求超时返回 408，默认 10s
+        .layer(TimeoutLayer::with_status_code(
+            StatusCode::REQUEST_TIMEOUT,
+            Duration::from_secs(app_config.server().request_timeout()),
+        ))
+        .layer(metrics_layer)
+        // 每个请求在带有 request id 的 span 中处理，并记录耗时；set_request_id 在最外层先执行
+        .layer(axum::middleware::from_fn(trace_request))
+        .layer(axum::middleware::from_fn(set_request_id));
+    // 允许配置的其它站点跨域调用，没有配置时不加 CORS 头
+    let app = match app_config.security().cors_layer() {
+        Some(cors) => app.layer(cors),
+        None => app,
+    };
 
     // run our app with hyper
     // `axum::Server` is a re-export of `hyper::Server`
     log!("listening on http://{}", &addr);
     let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
-    axum::serve(listener, app.into_make_service())
-        .await
-        .unwrap();
+    // 收到 SIGTERM/SIGINT 后 /readyz 返回 503，等待 pre_stop_delay 后不再接受新连接，等待处理中的请求结束
+    use server_common::shutdown;
+    let pre_stop_delay = Duration::from_secs(app_config.server().pre_stop_delay());
+    let drain_timeout = Duration::from_secs(app_config.server().shutdown_timeout());
+    shutdown::serve(listener, app, pre_stop_delay, drain_timeout, {
+        let state = shutdown_state.clone();
+        move || state.start_draining()
+    })
+    .await?;
+
+    tracing::info!("Closing the database connection pool......");
+    shutdown_state.db().close_by_ref().await?;
+    tracing::info!("Server stopped");
 
     Ok(())
 }
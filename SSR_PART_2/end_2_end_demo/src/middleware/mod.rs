@@ -0,0 +1,3 @@
+//! Axum middleware wrapped around every route of the server.
+
+pub mod tracing_request;
@@ -0,0 +1,40 @@
+use std::time::Instant;
+
+use axum::extract::Request;
+use axum::middleware::Next;
+use axum::response::Response;
+use server_common::latency::Latency;
+use server_common::request_id::RequestId;
+use tracing::Instrument;
+
+/// Runs the request in a span carrying its [`RequestId`], method and path, so every event logged
+/// while handling it (server functions and SeaORM queries included) carries the request id, and
+/// logs the status and latency of the response.
+///
+/// Must be layered inside [`set_request_id`](server_common::request_id::set_request_id).
+pub async fn trace_request(request: Request, next: Next) -> Response {
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .map(|id| id.0.clone())
+        .unwrap_or_default();
+    let span = tracing::info_span!(
+        "request",
+        request_id = %request_id,
+        method = %request.method(),
+        path = %request.uri().path(),
+    );
+
+    async move {
+        let start = Instant::now();
+        let response = next.run(request).await;
+        tracing::info!(
+            latency = %Latency(start.elapsed()),
+            status = %response.status().as_u16(),
+            "finished processing request."
+        );
+        response
+    }
+    .instrument(span)
+    .await
+}
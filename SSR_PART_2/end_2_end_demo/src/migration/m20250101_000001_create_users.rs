@@ -0,0 +1,100 @@
+use sea_orm_migration::prelude::extension::postgres::Type;
+use sea_orm_migration::prelude::*;
+
+use super::unless_exists;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // sql/init.sql 也会创建 gender 类型，已经存在时跳过
+        let create_gender = manager.get_database_backend().build(
+            &Type::create()
+                .as_enum(Gender::Enum)
+                .values([Gender::Male, Gender::Female, Gender::Intersex])
+                .to_owned(),
+        );
+        manager
+            .get_connection()
+            .execute_unprepared(&unless_exists(create_gender))
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Users::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Users::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key()
+                            .check(Expr::col(Users::Id).gte(0)),
+                    )
+                    .col(ColumnDef::new(Users::Fullname).string_len(64).not_null())
+                    .col(ColumnDef::new(Users::Gender).custom(Gender::Enum))
+                    .col(
+                        ColumnDef::new(Users::Email)
+                            .string_len(64)
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(Users::PasswordHash).string_len(97).not_null())
+                    .col(
+                        ColumnDef::new(Users::CreateAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // initial values for users; sql/init.sql 可能已经插入，且此时 ws_id 已经是 NOT NULL，
+        // 所以不能依赖 ON CONFLICT，先判断是否存在
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "INSERT INTO users (id, fullname, email, password_hash) \
+                 SELECT 0, 'Super', 'super@none.com', '' WHERE NOT EXISTS (SELECT 1 FROM users WHERE id = 0)",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Users::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_type(Type::drop().name(Gender::Enum).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+    Fullname,
+    Gender,
+    Email,
+    PasswordHash,
+    CreateAt,
+}
+
+#[derive(DeriveIden)]
+enum Gender {
+    #[sea_orm(iden = "gender")]
+    Enum,
+    #[sea_orm(iden = "Male")]
+    Male,
+    #[sea_orm(iden = "Female")]
+    Female,
+    #[sea_orm(iden = "Intersex")]
+    Intersex,
+}
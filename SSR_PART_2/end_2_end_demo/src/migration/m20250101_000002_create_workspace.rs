@@ -0,0 +1,117 @@
+use sea_orm_migration::prelude::*;
+
+use super::unless_exists;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // workspace for users
+        manager
+            .create_table(
+                Table::create()
+                    .table(Workspace::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Workspace::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Workspace::Name)
+                            .string_len(32)
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(Workspace::OwnerId).big_integer().not_null())
+                    .col(
+                        ColumnDef::new(Workspace::CreateAt)
+                            .timestamp_with_time_zone()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("workspace_owner_id_fkey")
+                            .from(Workspace::Table, Workspace::OwnerId)
+                            .to(Users::Table, Users::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // sql/init.sql 已经建好的列、外键和数据都跳过
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .add_column_if_not_exists(ColumnDef::new(Users::WsId).big_integer())
+                    .to_owned(),
+            )
+            .await?;
+        let add_ws_id_fkey = manager.get_database_backend().build(
+            &ForeignKey::create()
+                .name("users_ws_id_fkey")
+                .from(Users::Table, Users::WsId)
+                .to(Workspace::Table, Workspace::Id)
+                .to_owned(),
+        );
+        let db = manager.get_connection();
+        db.execute_unprepared(&unless_exists(add_ws_id_fkey))
+            .await?;
+
+        // initial values for workspace
+        db.execute_unprepared(
+            "INSERT INTO workspace (id, name, owner_id) VALUES (0, 'ws-super', 0) ON CONFLICT (id) DO NOTHING",
+        )
+        .await?;
+        // 设置 NOT NULL 之前补齐所有还没有 workspace 的用户
+        db.execute_unprepared("UPDATE users SET ws_id = 0 WHERE ws_id IS NULL")
+            .await?;
+
+        // alter users table to make ws_id not null
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .modify_column(ColumnDef::new(Users::WsId).big_integer().not_null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .drop_foreign_key(Alias::new("users_ws_id_fkey"))
+                    .drop_column(Users::WsId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Workspace::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+    WsId,
+}
+
+#[derive(DeriveIden)]
+enum Workspace {
+    Table,
+    Id,
+    Name,
+    OwnerId,
+    CreateAt,
+}
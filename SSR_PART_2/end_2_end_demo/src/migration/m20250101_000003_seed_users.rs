@@ -0,0 +1,58 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+const SEED_USERS: [(&str, &str); 9] = [
+    ("Bobby", "bobby@none.co"),
+    ("Bobby", "bobby1@none.co"),
+    ("Bobby", "bobby2@none.co"),
+    ("Bobby2", "bobby3@none.co"),
+    ("Bobby2", "bobby4@none.co"),
+    ("Alice", "alice@none.co"),
+    ("Alice", "alice1@none.co"),
+    ("Alice2", "alice2@none.co"),
+    ("Alice3", "alice3@none.co"),
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // initial more values for users
+        let mut insert = Query::insert();
+        insert.into_table(Users::Table).columns([
+            Users::Fullname,
+            Users::Email,
+            Users::PasswordHash,
+            Users::WsId,
+        ]);
+        for (fullname, email) in SEED_USERS {
+            insert.values_panic([fullname.into(), email.into(), "".into(), 0_i64.into()]);
+        }
+        // sql/init.sql 已经插入过同样的用户
+        insert.on_conflict(OnConflict::column(Users::Email).do_nothing().to_owned());
+
+        manager.exec_stmt(insert).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let emails = SEED_USERS.map(|(_, email)| email);
+        manager
+            .exec_stmt(
+                Query::delete()
+                    .from_table(Users::Table)
+                    .and_where(Expr::col(Users::Email).is_in(emails))
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Fullname,
+    Email,
+    PasswordHash,
+    WsId,
+}
@@ -0,0 +1,66 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 用户变更记录；不加外键，彻底删除用户后仍保留其记录
+        manager
+            .create_table(
+                Table::create()
+                    .table(AuditLog::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(AuditLog::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(AuditLog::ActorId).big_integer().not_null())
+                    .col(ColumnDef::new(AuditLog::Action).string_len(16).not_null())
+                    .col(ColumnDef::new(AuditLog::TargetId).big_integer().not_null())
+                    .col(ColumnDef::new(AuditLog::Before).json_binary())
+                    .col(ColumnDef::new(AuditLog::After).json_binary())
+                    .col(
+                        ColumnDef::new(AuditLog::CreateAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("audit_log_target_id_idx")
+                    .table(AuditLog::Table)
+                    .col(AuditLog::TargetId)
+                    .col(AuditLog::Id)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(AuditLog::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum AuditLog {
+    Table,
+    Id,
+    ActorId,
+    Action,
+    TargetId,
+    Before,
+    After,
+    CreateAt,
+}
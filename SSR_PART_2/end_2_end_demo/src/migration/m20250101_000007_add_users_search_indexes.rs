@@ -0,0 +1,35 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // 搜索用户：pg_trgm 的 GIN 索引同时支持 ILIKE '%q%' 和相似度匹配 (%)
+        let db = manager.get_connection();
+        db.execute_unprepared("CREATE EXTENSION IF NOT EXISTS pg_trgm")
+            .await?;
+        db.execute_unprepared(
+            "CREATE INDEX IF NOT EXISTS users_fullname_trgm_idx ON users USING gin (fullname gin_trgm_ops)",
+        )
+        .await?;
+        db.execute_unprepared(
+            "CREATE INDEX IF NOT EXISTS users_email_trgm_idx ON users USING gin (email gin_trgm_ops)",
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // pg_trgm 可能被其它表使用，只删除索引
+        let db = manager.get_connection();
+        db.execute_unprepared("DROP INDEX IF EXISTS users_email_trgm_idx")
+            .await?;
+        db.execute_unprepared("DROP INDEX IF EXISTS users_fullname_trgm_idx")
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,64 @@
+//! Versioned database migrations, replacing the hand-run `sql/init.sql`.
+//!
+//! A database set up by `sql/init.sql` can be migrated too: the first migrations skip the types,
+//! columns, constraints and rows it already created.
+//!
+//! Run them with `end_2_end_demo migrate up|down|status`, or at startup by setting
+//! `database.auto_migrate: true` in `config/{RUN_ENV}.yaml`.
+
+pub use sea_orm_migration::prelude::*;
+
+mod m20250101_000001_create_users;
+mod m20250101_000002_create_workspace;
+mod m20250101_000003_seed_users;
+mod m20250101_000004_add_users_deleted_at;
+mod m20250101_000005_add_users_version;
+mod m20250101_000006_create_audit_log;
+mod m20250101_000007_add_users_search_indexes;
+
+pub struct Migrator;
+
+#[async_trait::async_trait]
+impl MigratorTrait for Migrator {
+    fn migrations() -> Vec<Box<dyn MigrationTrait>> {
+        vec![
+            Box::new(m20250101_000001_create_users::Migration),
+            Box::new(m20250101_000002_create_workspace::Migration),
+            Box::new(m20250101_000003_seed_users::Migration),
+            Box::new(m20250101_000004_add_users_deleted_at::Migration),
+            Box::new(m20250101_000005_add_users_version::Migration),
+            Box::new(m20250101_000006_create_audit_log::Migration),
+            Box::new(m20250101_000007_add_users_search_indexes::Migration),
+        ]
+    }
+}
+
+/// Wraps `statement` in a block that ignores the error raised when the object it creates, e.g. a
+/// type or a constraint, already exists.
+pub(crate) fn unless_exists(statement: impl std::fmt::Display) -> String {
+    format!(
+        "DO $$ BEGIN {}; EXCEPTION WHEN duplicate_object THEN NULL; END $$",
+        statement
+    )
+}
+
+/// Handles the `migrate <up|down|status> [steps]` subcommand of the server binary.
+///
+/// `up` applies all pending migrations (or `steps` of them), `down` rolls back the last
+/// migration (or `steps` of them), `status` logs which migrations are applied.
+pub async fn run_command(db: &sea_orm::DatabaseConnection, args: &[String]) -> anyhow::Result<()> {
+    let steps = args
+        .get(1)
+        .map(|steps| steps.parse::<u32>())
+        .transpose()
+        .map_err(|e| anyhow::anyhow!("invalid number of steps: {}", e))?;
+
+    match args.first().map(String::as_str) {
+        Some("up") => Migrator::up(db, steps).await?,
+        Some("down") => Migrator::down(db, steps.or(Some(1))).await?,
+        Some("status") => Migrator::status(db).await?,
+        _ => anyhow::bail!("usage: migrate <up|down|status> [steps]"),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,140 @@
+use crate::dto::audit_log_dto::AuditLogDto;
+use crate::dto::page::Page;
+use crate::errors::user_error::UserError;
+
+#[cfg(feature = "ssr")]
+use crate::dto::audit_action::AuditAction;
+
+#[cfg(feature = "ssr")]
+use crate::dto::page::MAX_PAGE;
+
+#[cfg(feature = "ssr")]
+use crate::entity::prelude::*;
+
+#[cfg(feature = "ssr")]
+use crate::entity::{audit_log, users};
+
+use leptos::server;
+
+#[cfg(feature = "ssr")]
+use leptos::prelude::expect_context;
+
+#[cfg(feature = "ssr")]
+use sea_orm::{prelude::*, QueryOrder, Set};
+
+#[cfg(feature = "ssr")]
+use std::collections::HashMap;
+
+#[cfg(feature = "ssr")]
+use crate::server_fn::user::UserAccess;
+
+#[cfg(feature = "ssr")]
+use crate::state::app_state::AppState;
+
+/// Number of audit log entries per page of [`get_audit_log`].
+pub const AUDIT_LOG_PAGE_SIZE: u64 = 10;
+
+/// Lists the changes made to user `user_id`, most recent first, [`AUDIT_LOG_PAGE_SIZE`] entries
+/// per `page` starting from `1`.
+///
+/// The user may be deleted, but must still exist in a workspace visible to the caller.
+#[server]
+pub async fn get_audit_log(user_id: i64, page: u64) -> Result<Page<AuditLogDto>, UserError> {
+    #[cfg(feature = "ssr")]
+    {
+        let state = expect_context::<AppState>();
+        let db = state.db();
+        let access = UserAccess::load(db).await?;
+
+        match Users::find_by_id(user_id).one(db).await? {
+            Some(user) if access.can_view(user.ws_id) => {}
+            _ => return Err(UserError::NotFound(user_id)),
+        }
+
+        let page = page.clamp(1, MAX_PAGE);
+        let paginator = AuditLog::find()
+            .filter(audit_log::Column::TargetId.eq(user_id))
+            .order_by_desc(audit_log::Column::Id)
+            .paginate(db, AUDIT_LOG_PAGE_SIZE);
+        let total = paginator.num_items().await?;
+        let entries = paginator.fetch_page(page - 1).await?;
+
+        // 一次查询出所有操作人的名字
+        let mut actor_ids: Vec<i64> = entries.iter().map(|entry| entry.actor_id).collect();
+        actor_ids.sort_unstable();
+        actor_ids.dedup();
+        let actor_names: HashMap<i64, String> = if actor_ids.is_empty() {
+            HashMap::new()
+        } else {
+            Users::find()
+                .filter(users::Column::Id.is_in(actor_ids))
+                .all(db)
+                .await?
+                .into_iter()
+                .map(|user| (user.id, user.fullname))
+                .collect()
+        };
+
+        let items = entries
+            .into_iter()
+            .map(|entry| AuditLogDto {
+                id: entry.id,
+                actor_id: entry.actor_id,
+                actor_name: actor_names.get(&entry.actor_id).cloned(),
+                action: entry.action,
+                target_id: entry.target_id,
+                before: entry.before,
+                after: entry.after,
+                create_at: entry.create_at.to_string().chars().take(19).collect(),
+            })
+            .collect();
+        Ok(Page::new(items, total, page, AUDIT_LOG_PAGE_SIZE))
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    unreachable!("get_audit_log should only run on the server");
+}
+
+/// Builds the audit log entry of `actor_id` applying `action` to user `target_id`, which was
+/// `before` and is now `after`.
+#[cfg(feature = "ssr")]
+pub(crate) fn audit_entry(
+    actor_id: i64,
+    action: AuditAction,
+    target_id: i64,
+    before: Option<&users::Model>,
+    after: Option<&users::Model>,
+) -> audit_log::ActiveModel {
+    audit_log::ActiveModel {
+        actor_id: Set(actor_id),
+        action: Set(action.as_str().to_string()),
+        target_id: Set(target_id),
+        before: Set(before.map(snapshot)),
+        after: Set(after.map(snapshot)),
+        ..Default::default()
+    }
+}
+
+/// Writes `entries`; call it with the transaction of the changes they record.
+#[cfg(feature = "ssr")]
+pub(crate) async fn write_audit_log<C: ConnectionTrait>(
+    db: &C,
+    entries: Vec<audit_log::ActiveModel>,
+) -> Result<(), DbErr> {
+    if !entries.is_empty() {
+        AuditLog::insert_many(entries)
+            .exec_without_returning(db)
+            .await?;
+    }
+    Ok(())
+}
+
+/// The user as JSON, without its password hash.
+#[cfg(feature = "ssr")]
+fn snapshot(user: &users::Model) -> Json {
+    let mut value = serde_json::to_value(user).expect("users::Model serializes to JSON");
+    if let Some(fields) = value.as_object_mut() {
+        fields.remove("passwordHash");
+    }
+    value
+}
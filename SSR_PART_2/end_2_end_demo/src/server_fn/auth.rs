@@ -0,0 +1,93 @@
+use crate::dto::auth_user::AuthUser;
+use crate::errors::user_error::UserError;
+
+use leptos::server;
+
+#[cfg(feature = "ssr")]
+use leptos::prelude::expect_context;
+
+#[cfg(feature = "ssr")]
+use crate::entity::users;
+
+#[cfg(feature = "ssr")]
+use sea_orm::{prelude::*, QueryFilter};
+
+#[cfg(feature = "ssr")]
+use crate::state::app_state::AppState;
+
+#[cfg(feature = "ssr")]
+use crate::auth::password::verify_password_blocking;
+
+#[cfg(feature = "ssr")]
+use crate::auth::session;
+
+/// Checks `email` and `password`, then starts a session and sends its cookie.
+#[server]
+pub async fn login(email: String, password: String) -> Result<AuthUser, UserError> {
+    #[cfg(feature = "ssr")]
+    {
+        let state = expect_context::<AppState>();
+        let db = state.db();
+
+        let user = users::Entity::find()
+            .filter(users::Column::Email.eq(email.trim()))
+            .filter(users::Column::DeletedAt.is_null())
+            .one(db)
+            .await?;
+
+        // 邮箱不存在和密码错误返回同样的错误，且同样要校验一次密码，避免从错误或耗时泄露哪些邮箱已注册
+        let password_hash = user.as_ref().map(|user| user.password_hash.clone());
+        let valid = verify_password_blocking(password, password_hash).await;
+        let user = match user {
+            Some(user) if valid => user,
+            _ => {
+                tracing::error!("failed login attempt for email = : {:?}", email);
+                return Err(UserError::InvalidCredentials);
+            }
+        };
+
+        // 作废请求中已有的 session，防止 session fixation
+        if let Some(token) = session::session_token() {
+            state.sessions().remove(&token);
+        }
+        let auth_user = AuthUser::from(user);
+        let token = state.sessions().create(auth_user.clone());
+        session::set_session_cookie(&token);
+
+        tracing::info!("user logged in with id = : {:?}", auth_user.id);
+        Ok(auth_user)
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    unreachable!("login should only run on the server");
+}
+
+/// Ends the current session, if any, and clears its cookie.
+#[server]
+pub async fn logout() -> Result<(), UserError> {
+    #[cfg(feature = "ssr")]
+    {
+        let state = expect_context::<AppState>();
+
+        if let Some(token) = session::session_token() {
+            state.sessions().remove(&token);
+        }
+        session::clear_session_cookie();
+        Ok(())
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    unreachable!("logout should only run on the server");
+}
+
+/// Returns the logged-in user, or `None` for anonymous visitors.
+#[server]
+pub async fn current_user() -> Result<Option<AuthUser>, UserError> {
+    #[cfg(feature = "ssr")]
+    {
+        Ok(session::current_user())
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    unreachable!("current_user should only run on the server");
+}
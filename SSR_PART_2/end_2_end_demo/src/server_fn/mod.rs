@@ -1 +1,4 @@
+pub mod audit_log;
+pub mod auth;
 pub mod user;
+pub mod workspace;
@@ -1,5 +1,14 @@
+use crate::dto::bulk_result::BulkResult;
+use crate::dto::import_report::ImportReport;
+use crate::dto::page::Page;
+use crate::dto::trashed_user_dto::TrashedUserDto;
+#[cfg(feature = "ssr")]
+use crate::dto::user_event::UserChange;
 use crate::dto::users_dto::UsersDto;
 use crate::dto::users_dto_sig::UsersDtoSig;
+use crate::dto::users_query::UsersQuery;
+use crate::errors::user_error::UserError;
+#[cfg(feature = "ssr")]
 use leptos::prelude::RwSignal;
 
 #[cfg(feature = "ssr")]
@@ -11,31 +20,61 @@ use crate::entity::users;
 #[cfg(feature = "ssr")]
 use crate::entity::users::ActiveModel;
 
-use leptos::{
-    prelude::{expect_context, ServerFnError},
-    server,
-};
+use leptos::server_fn::codec::{MultipartData, MultipartFormData};
+use leptos::server;
+
+#[cfg(feature = "ssr")]
+use leptos::prelude::expect_context;
+
+#[cfg(feature = "ssr")]
+use crate::dto::import_report::{ImportOutcome, ImportRowResult};
+
+#[cfg(feature = "ssr")]
+use crate::dto::users_csv_row::UsersCsvRow;
+
+#[cfg(feature = "ssr")]
+use crate::dto::users_query::{SortDirection, UsersSortColumn};
 
 #[cfg(feature = "ssr")]
-use sea_orm::{prelude::*, Condition, QueryOrder, Set};
+use sea_orm::{prelude::*, Condition, Order, QueryOrder, Set, TransactionTrait};
+
+#[cfg(feature = "ssr")]
+use std::collections::HashMap;
 
 #[cfg(feature = "ssr")]
 use crate::state::app_state::AppState;
 
+#[cfg(feature = "ssr")]
+use crate::auth::password::{hash_password_blocking, verify_password_blocking, MIN_PASSWORD_LEN};
+
+#[cfg(feature = "ssr")]
+use crate::auth::session::{require_user, session_token};
+
+#[cfg(feature = "ssr")]
+use crate::entity::{audit_log, workspace};
+
+#[cfg(feature = "ssr")]
+use crate::dto::audit_action::AuditAction;
+
+#[cfg(feature = "ssr")]
+use crate::server_fn::audit_log::{audit_entry, write_audit_log};
+
+#[cfg(feature = "ssr")]
+use server_common::request_id::current_request_id;
+
 #[server]
-pub async fn get_users_sig() -> Result<Vec<UsersDtoSig>, ServerFnError> {
+#[cfg_attr(
+    feature = "ssr",
+    tracing::instrument(skip_all, fields(request_id = %current_request_id().unwrap_or_default()))
+)]
+pub async fn get_users_sig(query: UsersQuery) -> Result<Page<UsersDtoSig>, UserError> {
     #[cfg(feature = "ssr")]
     {
         let state = expect_context::<AppState>();
         let db = state.db();
+        let access = UserAccess::load(db).await?;
 
-        let conditions = Condition::all();
-        let users = Users::find()
-            .filter(conditions)
-            .order_by_desc(users::Column::CreateAt)
-            .all(db)
-            .await
-            .unwrap();
+        let (users, total) = find_users_page(db, &query, &access.visible_ws_ids()).await?;
         // tracing::info!("query users results: {:?}", users);
         // ApiResponse::success("success", Some(users))
 
@@ -45,6 +84,7 @@ pub async fn get_users_sig() -> Result<Vec<UsersDtoSig>, ServerFnError> {
                 // key: user.id.to_string(),
                 id: user.id,
                 fullname: RwSignal::new(user.fullname),
+                gender: RwSignal::new(user.gender.map(Into::into)),
                 email: RwSignal::new(user.email),
                 create_at: Some(
                     user.create_at
@@ -55,11 +95,17 @@ pub async fn get_users_sig() -> Result<Vec<UsersDtoSig>, ServerFnError> {
                         .collect(),
                 ),
                 ws_id: RwSignal::new(user.ws_id),
+                version: RwSignal::new(user.version),
             })
             .collect();
 
         // tracing::info!("query users_dto results: {:?}", users_dto);
-        Ok(users_dto_sig)
+        Ok(Page::new(
+            users_dto_sig,
+            total,
+            query.page(),
+            query.page_size(),
+        ))
     }
 
     #[cfg(not(feature = "ssr"))]
@@ -67,73 +113,109 @@ pub async fn get_users_sig() -> Result<Vec<UsersDtoSig>, ServerFnError> {
 }
 
 #[server]
-pub async fn get_users() -> Result<Vec<UsersDto>, ServerFnError> {
+#[cfg_attr(
+    feature = "ssr",
+    tracing::instrument(skip_all, fields(request_id = %current_request_id().unwrap_or_default()))
+)]
+pub async fn get_users(query: UsersQuery) -> Result<Page<UsersDto>, UserError> {
     #[cfg(feature = "ssr")]
     {
         let state = expect_context::<AppState>();
         let db = state.db();
+        let access = UserAccess::load(db).await?;
 
-        let conditions = Condition::all();
-        let users = Users::find()
-            .filter(conditions)
-            .order_by_desc(users::Column::CreateAt)
-            .all(db)
-            .await
-            .unwrap();
+        let (users, total) = find_users_page(db, &query, &access.visible_ws_ids()).await?;
         // tracing::info!("query users results: {:?}", users);
         // ApiResponse::success("success", Some(users))
 
-        let users_dto: Vec<_> = users
-            .into_iter()
-            .map(|user| UsersDto {
-                // key: user.id.to_string(),
-                id: user.id,
-                fullname: user.fullname,
-                email: user.email,
-                create_at: Some(
-                    user.create_at
-                        .unwrap()
-                        .to_string()
-                        .chars()
-                        .take(16)
-                        .collect(),
-                ),
-                ws_id: user.ws_id,
-            })
-            .collect();
+        let users_dto: Vec<_> = users.into_iter().map(UsersDto::from).collect();
 
         // tracing::info!("query users_dto results: {:?}", users_dto);
-        Ok(users_dto)
+        Ok(Page::new(users_dto, total, query.page(), query.page_size()))
     }
 
     #[cfg(not(feature = "ssr"))]
     unreachable!("get_users should only run on the server");
 }
 
+/// Searches the visible users whose `fullname` or `email` match `query.q`, within the filters and
+/// paging of `query`.
+///
+/// `q` matches as a case-insensitive substring, or by trigram similarity (Postgres `pg_trgm`) so that
+/// small typos still match. Results are ordered by similarity first, then by the sort of `query`.
 #[server]
-pub async fn delete_users(id: i64) -> Result<bool, ServerFnError> {
+#[cfg_attr(
+    feature = "ssr",
+    tracing::instrument(skip_all, fields(request_id = %current_request_id().unwrap_or_default()))
+)]
+pub async fn search_users(query: UsersQuery) -> Result<Page<UsersDtoSig>, UserError> {
+    #[cfg(feature = "ssr")]
+    {
+        tracing::info!("search users: {:?}", query.q);
+        get_users_sig(query).await
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    unreachable!("search_users should only run on the server");
+}
+
+/// Moves user `id` to the trash by setting `deleted_at`; see [`restore_users`] and [`purge_users`].
+///
+/// Only the owner of the user's workspace may delete it.
+#[server]
+#[cfg_attr(
+    feature = "ssr",
+    tracing::instrument(skip_all, fields(request_id = %current_request_id().unwrap_or_default()))
+)]
+pub async fn delete_users(id: i64) -> Result<(), UserError> {
     #[cfg(feature = "ssr")]
     {
         let state = expect_context::<AppState>();
         let db = state.db();
-        let rt = users::Entity::delete_by_id(id as i64).exec(db).await;
+        let access = UserAccess::load(db).await?;
+
+        let txn = db.begin().await?;
+        // 只有用户所在分组的 owner 可以删除
+        let target = access.find_visible_user(&txn, id).await?;
+        if !access.owns(target.ws_id) {
+            tracing::error!(
+                "user id = : {:?} is not allowed to delete user id = : {:?}",
+                access.user.id,
+                id
+            );
+            return Err(forbidden());
+        }
+
+        let rt = Users::update_many()
+            .col_expr(users::Column::DeletedAt, Expr::current_timestamp().into())
+            .filter(users::Column::Id.eq(id))
+            .filter(users::Column::DeletedAt.is_null())
+            .exec_with_returning(&txn)
+            .await;
 
         match rt {
-            Ok(deleted_user) => {
-                if deleted_user.rows_affected > 0 {
+            Ok(deleted) => {
+                if let Some(deleted_user) = deleted.first() {
+                    let entry = audit_entry(
+                        access.user.id,
+                        AuditAction::Delete,
+                        id,
+                        Some(&target),
+                        Some(deleted_user),
+                    );
+                    write_audit_log(&txn, vec![entry]).await?;
+                    txn.commit().await?;
                     tracing::info!("User was deleted successfully with id = : {:?}!", id);
-                    // ApiResponse::success("User was deleted successfully!", None)
-                    return Ok(true);
+                    state.publish_user_event(UserChange::Removed(vec![(id, target.ws_id)]));
+                    Ok(())
                 } else {
                     tracing::error!("When delete the user, with id = : {:?} not found", id);
-                    // ApiResponse::error(format!("User with id = : {:?} not found", id))
-                    return Ok(false);
+                    Err(UserError::NotFound(id))
                 }
             }
             Err(e) => {
                 tracing::error!("error deleting user: {:?}", e);
-                // ApiResponse::error(format!("error deleting user: {:?}", e))
-                return Ok(false);
+                Err(e.into())
             }
         }
     }
@@ -142,73 +224,189 @@ pub async fn delete_users(id: i64) -> Result<bool, ServerFnError> {
     unreachable!("delete_users should only run on the server");
 }
 
+/// Creates the user when `users_dto.id` is `-1`, otherwise updates it.
+///
+/// The user is checked with [`UsersDto::validate`] and `ws_id` must exist, otherwise
+/// [`UserError::Validation`] lists the invalid fields.
+///
+/// Users can only be created in and updated within workspaces visible to the caller, and only the
+/// owner of the user's workspace may move it to another `ws_id`.
+///
+/// Updates only apply if `users_dto.version` is still the user's current version; otherwise the
+/// user was changed by someone else in the meantime and [`UserError::Conflict`] returns its
+/// current values.
+///
+/// `password` and `password_confirm` are only used, and required, when creating a user;
+/// existing users change their password through [`change_password`].
 #[server]
-pub async fn add_or_update_users(users_dto: UsersDto) -> Result<UsersDto, ServerFnError> {
+#[cfg_attr(
+    feature = "ssr",
+    tracing::instrument(skip_all, fields(request_id = %current_request_id().unwrap_or_default()))
+)]
+pub async fn add_or_update_users(
+    users_dto: UsersDto,
+    password: Option<String>,
+    password_confirm: Option<String>,
+) -> Result<UsersDto, UserError> {
     tracing::info!("users_dto: {:?}", users_dto);
     #[cfg(feature = "ssr")]
     {
         let state = expect_context::<AppState>();
         let db = state.db();
+        let access = UserAccess::load(db).await?;
+
+        // 去掉首尾空白后再校验和保存，长度检查才与数据库一致
+        let users_dto = users_dto.normalized();
+        // 可见的分组一定存在，只有不可见时才需要查询分组是否存在
+        let mut errors = users_dto.validate();
+        if !errors.contains_key("ws_id")
+            && !access.can_view(users_dto.ws_id)
+            && Workspace::find_by_id(users_dto.ws_id)
+                .one(db)
+                .await?
+                .is_none()
+        {
+            errors.insert("ws_id".to_string(), "workspace does not exist".to_string());
+        }
+        if !errors.is_empty() {
+            tracing::error!("invalid users_dto: {:?}", errors);
+            return Err(UserError::Validation(errors));
+        }
 
         // 更新
         if users_dto.id > -1 {
-            let rt = users::Entity::update(users::ActiveModel {
-                id: Set(users_dto.id),
-                fullname: Set(users_dto.fullname),
-                email: Set(users_dto.email),
-                ws_id: Set(users_dto.ws_id as i64), 
-                ..Default::default()
-            })
-            .exec(db)
-            .await;
+            let txn = db.begin().await?;
+            // 只有用户所在分组的 owner 可以把用户移动到其它分组
+            let target = access.find_visible_user(&txn, users_dto.id).await?;
+            if users_dto.ws_id != target.ws_id
+                && !(access.owns(target.ws_id) && access.can_view(users_dto.ws_id))
+            {
+                tracing::error!(
+                    "user id = : {:?} is not allowed to move user id = : {:?} to ws_id = : {:?}",
+                    access.user.id,
+                    users_dto.id,
+                    users_dto.ws_id
+                );
+                return Err(forbidden());
+            }
+
+            let email = users_dto.email.clone();
+            // 乐观锁：只有版本与客户端最后看到的一致时才更新，同时版本 +1
+            let rt = Users::update_many()
+                .set(users::ActiveModel {
+                    fullname: Set(users_dto.fullname),
+                    gender: Set(users_dto.gender.map(Into::into)),
+                    email: Set(users_dto.email),
+                    ws_id: Set(users_dto.ws_id),
+                    ..Default::default()
+                })
+                .col_expr(
+                    users::Column::Version,
+                    Expr::col(users::Column::Version).add(1),
+                )
+                .filter(users::Column::Id.eq(users_dto.id))
+                .filter(users::Column::Version.eq(users_dto.version))
+                .filter(users::Column::DeletedAt.is_null())
+                .exec_with_returning(&txn)
+                .await;
 
             match rt {
-                Ok(user) => {
+                Ok(mut updated) if !updated.is_empty() => {
+                    let user = updated.remove(0);
+                    let entry = audit_entry(
+                        access.user.id,
+                        AuditAction::Update,
+                        user.id,
+                        Some(&target),
+                        Some(&user),
+                    );
+                    write_audit_log(&txn, vec![entry]).await?;
+                    txn.commit().await?;
                     tracing::info!(
                         "user updated successfully with id = : {:?}, name = : {:?}",
                         user.id,
                         user.fullname
                     );
-                    Ok(UsersDto::new(
-                        user.id,
-                        user.fullname,
-                        user.email,
-                        None,
-                        user.ws_id,
-                    ))
+                    let mut users_dto = UsersDto::from(user);
+                    state.publish_user_event(UserChange::Changed(vec![(
+                        users_dto.clone(),
+                        Some(target.ws_id),
+                    )]));
+                    // 创建时间为 None 表示更新
+                    users_dto.create_at = None;
+                    Ok(users_dto)
                 }
-                Err(DbErr::RecordNotUpdated) => {
-                    tracing::error!("User id: {} not found", users_dto.id);
-                    Ok(UsersDto::default())
+                // 没有更新任何行：用户已被删除，或者已被其他人修改
+                Ok(_) => {
+                    let current = access.find_visible_user(&txn, users_dto.id).await?;
+                    tracing::error!(
+                        "user id = : {:?} was changed by someone else, version = : {:?}, current version = : {:?}",
+                        users_dto.id,
+                        users_dto.version,
+                        current.version
+                    );
+                    Err(conflict(current))
                 }
                 Err(e) => {
                     tracing::error!("error updating user: {:?}", e);
-                    Ok(UsersDto::default())
+                    Err(user_db_error(db, e, &email).await)
                 }
             }
         }
         // 新增
         else {
+            if !access.can_view(users_dto.ws_id) {
+                tracing::error!(
+                    "user id = : {:?} is not allowed to add users to ws_id = : {:?}",
+                    access.user.id,
+                    users_dto.ws_id
+                );
+                return Err(forbidden());
+            }
+
+            let password = password.unwrap_or_default();
+            check_new_password(&password, &password_confirm.unwrap_or_default())?;
+            let password_hash = hash_password_blocking(password)
+                .await
+                .map_err(|e| UserError::PasswordHash(e.to_string()))?;
+
+            let email = users_dto.email.clone();
             let new_user = ActiveModel {
                 fullname: Set(users_dto.fullname),
+                gender: Set(users_dto.gender.map(Into::into)),
                 email: Set(users_dto.email),
-                password_hash: Set("test".to_string()),
+                password_hash: Set(password_hash),
                 ws_id: Set(users_dto.ws_id),
                 ..Default::default()
             };
 
-            let rt = new_user.insert(db).await;
+            let txn = db.begin().await?;
+            let rt = new_user.insert(&txn).await;
 
             match rt {
                 Ok(user) => {
+                    let entry = audit_entry(
+                        access.user.id,
+                        AuditAction::Create,
+                        user.id,
+                        None,
+                        Some(&user),
+                    );
+                    write_audit_log(&txn, vec![entry]).await?;
+                    txn.commit().await?;
                     tracing::info!(
                         "user created successfully with id = : {:?} and name = : {:?}",
                         user.id,
                         user.fullname
                     );
+                    state.publish_user_event(UserChange::Changed(vec![(
+                        UsersDto::from(user.clone()),
+                        None,
+                    )]));
                     Ok(UsersDto::new(
                         user.id,
                         user.fullname,
+                        user.gender.map(Into::into),
                         user.email,
                         Some(user.create_at.unwrap().to_string()),
                         user.ws_id,
@@ -216,8 +414,7 @@ pub async fn add_or_update_users(users_dto: UsersDto) -> Result<UsersDto, Server
                 }
                 Err(e) => {
                     tracing::error!("error creating user: {:?}", e);
-                    // ApiResponse::error(format!("error creating user: {:?}", e))
-                    Ok(UsersDto::default())
+                    Err(user_db_error(db, e, &email).await)
                 }
             }
         }
@@ -226,3 +423,810 @@ pub async fn add_or_update_users(users_dto: UsersDto) -> Result<UsersDto, Server
     #[cfg(not(feature = "ssr"))]
     unreachable!("delete_users should only run on the server");
 }
+
+/// Moves the users `ids` to the trash in a single transaction.
+///
+/// Ids that cannot be deleted, because they are missing or the caller does not own their workspace,
+/// are reported in the per-id results and do not stop the others from being deleted.
+#[server]
+#[cfg_attr(
+    feature = "ssr",
+    tracing::instrument(skip_all, fields(request_id = %current_request_id().unwrap_or_default()))
+)]
+pub async fn delete_users_bulk(ids: Vec<i64>) -> Result<Vec<BulkResult>, UserError> {
+    #[cfg(feature = "ssr")]
+    {
+        let state = expect_context::<AppState>();
+        let db = state.db();
+        let access = UserAccess::load(db).await?;
+
+        let txn = db.begin().await?;
+        let targets = access.find_visible_users(&txn, &ids, false).await?;
+
+        let mut deletable = vec![];
+        let results: Vec<_> = ids
+            .iter()
+            .map(|&id| match targets.get(&id) {
+                None => BulkResult::err(id, UserError::NotFound(id)),
+                Some(user) if !access.owns(user.ws_id) => BulkResult::err(id, UserError::Forbidden),
+                Some(_) => {
+                    deletable.push(id);
+                    BulkResult::ok(id)
+                }
+            })
+            .collect();
+
+        if !deletable.is_empty() {
+            let deleted = Users::update_many()
+                .col_expr(users::Column::DeletedAt, Expr::current_timestamp().into())
+                .filter(users::Column::Id.is_in(deletable.iter().copied()))
+                .exec_with_returning(&txn)
+                .await?;
+            let entries = audit_entries(access.user.id, AuditAction::Delete, &targets, &deleted);
+            write_audit_log(&txn, entries).await?;
+        }
+        txn.commit().await?;
+
+        tracing::info!(
+            "users were deleted successfully with ids = : {:?}",
+            deletable
+        );
+        if !deletable.is_empty() {
+            let removed = deletable.iter().map(|id| (*id, targets[id].ws_id)).collect();
+            state.publish_user_event(UserChange::Removed(removed));
+        }
+        Ok(results)
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    unreachable!("delete_users_bulk should only run on the server");
+}
+
+/// Moves the users `ids` to workspace `ws_id` in a single transaction.
+///
+/// The caller must be able to see `ws_id` and own the current workspace of every moved user;
+/// ids failing the latter are reported in the per-id results and are left where they are.
+#[server]
+#[cfg_attr(
+    feature = "ssr",
+    tracing::instrument(skip_all, fields(request_id = %current_request_id().unwrap_or_default()))
+)]
+pub async fn move_users(ids: Vec<i64>, ws_id: i64) -> Result<Vec<BulkResult>, UserError> {
+    #[cfg(feature = "ssr")]
+    {
+        let state = expect_context::<AppState>();
+        let db = state.db();
+        let access = UserAccess::load(db).await?;
+
+        if !access.can_view(ws_id) {
+            if Workspace::find_by_id(ws_id).one(db).await?.is_none() {
+                return Err(UserError::WorkspaceNotFound);
+            }
+            return Err(forbidden());
+        }
+
+        let txn = db.begin().await?;
+        let targets = access.find_visible_users(&txn, &ids, false).await?;
+
+        let mut movable = vec![];
+        let results: Vec<_> = ids
+            .iter()
+            .map(|&id| match targets.get(&id) {
+                None => BulkResult::err(id, UserError::NotFound(id)),
+                Some(user) if user.ws_id == ws_id => BulkResult::ok(id),
+                Some(user) if !access.owns(user.ws_id) => BulkResult::err(id, UserError::Forbidden),
+                Some(_) => {
+                    movable.push(id);
+                    BulkResult::ok(id)
+                }
+            })
+            .collect();
+
+        let mut moved = vec![];
+        if !movable.is_empty() {
+            moved = Users::update_many()
+                .col_expr(users::Column::WsId, Expr::value(ws_id))
+                .col_expr(
+                    users::Column::Version,
+                    Expr::col(users::Column::Version).add(1),
+                )
+                .filter(users::Column::Id.is_in(movable.iter().copied()))
+                .exec_with_returning(&txn)
+                .await?;
+            let entries = audit_entries(access.user.id, AuditAction::Move, &targets, &moved);
+            write_audit_log(&txn, entries).await?;
+        }
+        txn.commit().await?;
+
+        tracing::info!(
+            "users were moved successfully with ids = : {:?} to ws_id = : {:?}",
+            movable,
+            ws_id
+        );
+        if !moved.is_empty() {
+            let moved = moved
+                .into_iter()
+                .map(|user| {
+                    let before = targets.get(&user.id).map(|before| before.ws_id);
+                    (UsersDto::from(user), before)
+                })
+                .collect();
+            state.publish_user_event(UserChange::Changed(moved));
+        }
+        Ok(results)
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    unreachable!("move_users should only run on the server");
+}
+
+/// Lists the deleted users of the visible workspaces, most recently deleted first.
+#[server]
+#[cfg_attr(
+    feature = "ssr",
+    tracing::instrument(skip_all, fields(request_id = %current_request_id().unwrap_or_default()))
+)]
+pub async fn get_deleted_users() -> Result<Vec<TrashedUserDto>, UserError> {
+    #[cfg(feature = "ssr")]
+    {
+        let state = expect_context::<AppState>();
+        let db = state.db();
+        let access = UserAccess::load(db).await?;
+
+        let users = Users::find()
+            .filter(users::Column::DeletedAt.is_not_null())
+            .filter(users::Column::WsId.is_in(access.visible_ws_ids()))
+            .order_by_desc(users::Column::DeletedAt)
+            .order_by_desc(users::Column::Id)
+            .all(db)
+            .await?;
+
+        Ok(users.into_iter().map(TrashedUserDto::from).collect())
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    unreachable!("get_deleted_users should only run on the server");
+}
+
+/// Restores the deleted users `ids` from the trash in a single transaction.
+///
+/// Like deleting, restoring requires owning the user's workspace.
+#[server]
+#[cfg_attr(
+    feature = "ssr",
+    tracing::instrument(skip_all, fields(request_id = %current_request_id().unwrap_or_default()))
+)]
+pub async fn restore_users(ids: Vec<i64>) -> Result<Vec<BulkResult>, UserError> {
+    #[cfg(feature = "ssr")]
+    {
+        let state = expect_context::<AppState>();
+        let db = state.db();
+        let access = UserAccess::load(db).await?;
+
+        let txn = db.begin().await?;
+        let targets = access.find_visible_users(&txn, &ids, true).await?;
+
+        let mut restorable = vec![];
+        let results: Vec<_> = ids
+            .iter()
+            .map(|&id| match targets.get(&id) {
+                None => BulkResult::err(id, UserError::NotFound(id)),
+                Some(user) if !access.owns(user.ws_id) => BulkResult::err(id, UserError::Forbidden),
+                Some(_) => {
+                    restorable.push(id);
+                    BulkResult::ok(id)
+                }
+            })
+            .collect();
+
+        let mut restored = vec![];
+        if !restorable.is_empty() {
+            restored = Users::update_many()
+                .col_expr(
+                    users::Column::DeletedAt,
+                    Expr::value(Option::<DateTimeWithTimeZone>::None),
+                )
+                .filter(users::Column::Id.is_in(restorable.iter().copied()))
+                .exec_with_returning(&txn)
+                .await?;
+            let entries = audit_entries(access.user.id, AuditAction::Restore, &targets, &restored);
+            write_audit_log(&txn, entries).await?;
+        }
+        txn.commit().await?;
+
+        tracing::info!(
+            "users were restored successfully with ids = : {:?}",
+            restorable
+        );
+        if !restored.is_empty() {
+            // 回收站中的用户不在任何列表中
+            let restored = restored
+                .into_iter()
+                .map(|user| (UsersDto::from(user), None))
+                .collect();
+            state.publish_user_event(UserChange::Changed(restored));
+        }
+        Ok(results)
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    unreachable!("restore_users should only run on the server");
+}
+
+/// Permanently deletes the deleted users `ids` in a single transaction.
+///
+/// Requires owning the user's workspace; users that still own a workspace cannot be purged.
+#[server]
+#[cfg_attr(
+    feature = "ssr",
+    tracing::instrument(skip_all, fields(request_id = %current_request_id().unwrap_or_default()))
+)]
+pub async fn purge_users(ids: Vec<i64>) -> Result<Vec<BulkResult>, UserError> {
+    #[cfg(feature = "ssr")]
+    {
+        let state = expect_context::<AppState>();
+        let db = state.db();
+        let access = UserAccess::load(db).await?;
+
+        let txn = db.begin().await?;
+        let targets = access.find_visible_users(&txn, &ids, true).await?;
+        let owners: Vec<i64> = Workspace::find()
+            .filter(workspace::Column::OwnerId.is_in(targets.keys().copied()))
+            .all(&txn)
+            .await?
+            .into_iter()
+            .map(|ws| ws.owner_id)
+            .collect();
+
+        let mut purgeable = vec![];
+        let results: Vec<_> = ids
+            .iter()
+            .map(|&id| match targets.get(&id) {
+                None => BulkResult::err(id, UserError::NotFound(id)),
+                Some(user) if !access.owns(user.ws_id) => BulkResult::err(id, UserError::Forbidden),
+                Some(_) if owners.contains(&id) => BulkResult::err(id, UserError::OwnsWorkspace),
+                Some(_) => {
+                    purgeable.push(id);
+                    BulkResult::ok(id)
+                }
+            })
+            .collect();
+
+        if !purgeable.is_empty() {
+            Users::delete_many()
+                .filter(users::Column::Id.is_in(purgeable.iter().copied()))
+                .filter(users::Column::DeletedAt.is_not_null())
+                .exec(&txn)
+                .await?;
+            let entries = purgeable
+                .iter()
+                .filter_map(|id| targets.get(id))
+                .map(|user| {
+                    audit_entry(
+                        access.user.id,
+                        AuditAction::Purge,
+                        user.id,
+                        Some(user),
+                        None,
+                    )
+                })
+                .collect();
+            write_audit_log(&txn, entries).await?;
+        }
+        txn.commit().await?;
+
+        tracing::info!(
+            "users were purged successfully with ids = : {:?}",
+            purgeable
+        );
+        Ok(results)
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    unreachable!("purge_users should only run on the server");
+}
+
+/// Imports users from the CSV file in the `file` field of `data`, see [`UsersCsvRow`] for the columns.
+///
+/// Rows are matched to existing users by email: matching users are updated, the others are
+/// created without a password, so they cannot log in until one is set with the `set-password`
+/// subcommand. Invalid rows, and rows the caller may not change, are skipped and reported; all
+/// other rows are imported in a single transaction.
+#[server(input = MultipartFormData)]
+#[cfg_attr(
+    feature = "ssr",
+    tracing::instrument(skip_all, fields(request_id = %current_request_id().unwrap_or_default()))
+)]
+pub async fn import_users_csv(data: MultipartData) -> Result<ImportReport, UserError> {
+    #[cfg(feature = "ssr")]
+    {
+        let state = expect_context::<AppState>();
+        let db = state.db();
+        let access = UserAccess::load(db).await?;
+
+        // 读取上传的文件
+        let mut data = data
+            .into_inner()
+            .ok_or_else(|| UserError::InvalidCsv("expected a multipart form".to_string()))?;
+        let mut content = vec![];
+        while let Some(field) = data
+            .next_field()
+            .await
+            .map_err(|e| UserError::InvalidCsv(e.to_string()))?
+        {
+            if field.name() == Some("file") {
+                content = field
+                    .bytes()
+                    .await
+                    .map_err(|e| UserError::InvalidCsv(e.to_string()))?
+                    .to_vec();
+            }
+        }
+
+        let mut reader = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_reader(content.as_slice());
+        let headers = reader
+            .headers()
+            .map_err(|e| UserError::InvalidCsv(e.to_string()))?
+            .clone();
+
+        let txn = db.begin().await?;
+        let mut report = ImportReport::default();
+        let mut changed = vec![];
+        for record in reader.records() {
+            let row = match record {
+                Ok(record) => {
+                    let line = record.position().map(|pos| pos.line()).unwrap_or_default();
+                    match record.deserialize::<UsersCsvRow>(Some(&headers)) {
+                        Ok(row) => {
+                            let email = row.email.clone();
+                            let outcome = import_row(&txn, &access, row, &mut changed).await?;
+                            ImportRowResult {
+                                line,
+                                email,
+                                outcome,
+                            }
+                        }
+                        Err(e) => ImportRowResult {
+                            line,
+                            email: String::new(),
+                            outcome: ImportOutcome::Failed(e.to_string()),
+                        },
+                    }
+                }
+                Err(e) => ImportRowResult {
+                    line: e.position().map(|pos| pos.line()).unwrap_or_default(),
+                    email: String::new(),
+                    outcome: ImportOutcome::Failed(e.to_string()),
+                },
+            };
+            report.rows.push(row);
+        }
+        txn.commit().await?;
+
+        tracing::info!(
+            "users imported: {:?} imported, {:?} failed",
+            report.imported(),
+            report.failed()
+        );
+        if !changed.is_empty() {
+            state.publish_user_event(UserChange::Changed(changed));
+        }
+        Ok(report)
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    unreachable!("import_users_csv should only run on the server");
+}
+
+/// Creates or updates the user of one imported CSV row, applying the same rules as
+/// [`add_or_update_users`]. Imported users are added to `changed` with their previous workspace.
+#[cfg(feature = "ssr")]
+async fn import_row<C: ConnectionTrait>(
+    db: &C,
+    access: &UserAccess,
+    row: UsersCsvRow,
+    changed: &mut Vec<(UsersDto, Option<i64>)>,
+) -> Result<ImportOutcome, DbErr> {
+    let users_dto = match row.into_users_dto() {
+        Ok(users_dto) => users_dto,
+        Err(msg) => return Ok(ImportOutcome::Failed(msg)),
+    };
+    let errors = users_dto.validate();
+    if !errors.is_empty() {
+        let msg = errors
+            .iter()
+            .map(|(field, msg)| format!("{}: {}", field, msg))
+            .collect::<Vec<_>>()
+            .join("; ");
+        return Ok(ImportOutcome::Failed(msg));
+    }
+    if !access.can_view(users_dto.ws_id) {
+        return Ok(ImportOutcome::Failed(format!(
+            "workspace {} does not exist or is not accessible",
+            users_dto.ws_id
+        )));
+    }
+
+    let existing = Users::find()
+        .filter(users::Column::Email.eq(users_dto.email.as_str()))
+        .one(db)
+        .await?;
+
+    match existing {
+        Some(user) if user.deleted_at.is_some() => {
+            Ok(ImportOutcome::Failed(UserError::EmailInTrash.to_string()))
+        }
+        Some(user) if !access.can_view(user.ws_id) => {
+            Ok(ImportOutcome::Failed(UserError::DuplicateEmail.to_string()))
+        }
+        Some(user) if user.ws_id != users_dto.ws_id && !access.owns(user.ws_id) => {
+            Ok(ImportOutcome::Failed(UserError::Forbidden.to_string()))
+        }
+        Some(before) => {
+            let mut user: users::ActiveModel = before.clone().into();
+            user.fullname = Set(users_dto.fullname);
+            user.gender = Set(users_dto.gender.map(Into::into));
+            user.ws_id = Set(users_dto.ws_id);
+            user.version = Set(before.version + 1);
+            let user = user.update(db).await?;
+            let entry = audit_entry(
+                access.user.id,
+                AuditAction::Import,
+                user.id,
+                Some(&before),
+                Some(&user),
+            );
+            write_audit_log(db, vec![entry]).await?;
+            let id = user.id;
+            changed.push((UsersDto::from(user), Some(before.ws_id)));
+            Ok(ImportOutcome::Updated(id))
+        }
+        None => {
+            let user = ActiveModel {
+                fullname: Set(users_dto.fullname),
+                gender: Set(users_dto.gender.map(Into::into)),
+                email: Set(users_dto.email),
+                // 没有密码，无法登录，需要用 set-password 子命令设置
+                password_hash: Set(String::new()),
+                ws_id: Set(users_dto.ws_id),
+                ..Default::default()
+            }
+            .insert(db)
+            .await?;
+            let entry = audit_entry(
+                access.user.id,
+                AuditAction::Import,
+                user.id,
+                None,
+                Some(&user),
+            );
+            write_audit_log(db, vec![entry]).await?;
+            let id = user.id;
+            changed.push((UsersDto::from(user), None));
+            Ok(ImportOutcome::Created(id))
+        }
+    }
+}
+
+/// Changes the password of user `id` after verifying `old_password`.
+///
+/// Users may change their own password; owners also the passwords of the users of the workspaces
+/// they own.
+#[server]
+#[cfg_attr(
+    feature = "ssr",
+    tracing::instrument(skip_all, fields(request_id = %current_request_id().unwrap_or_default()))
+)]
+pub async fn change_password(
+    id: i64,
+    old_password: String,
+    new_password: String,
+    new_password_confirm: String,
+) -> Result<(), UserError> {
+    #[cfg(feature = "ssr")]
+    {
+        let state = expect_context::<AppState>();
+        let db = state.db();
+        let access = UserAccess::load(db).await?;
+
+        // 只能修改自己的密码，或者自己拥有的分组中用户的密码
+        let user = access.find_visible_user(db, id).await?;
+        if user.id != access.user_id() && !access.owns(user.ws_id) {
+            tracing::error!(
+                "user id = : {:?} is not allowed to change the password of user id = : {:?}",
+                access.user_id(),
+                id
+            );
+            return Err(forbidden());
+        }
+
+        if !verify_password_blocking(old_password, Some(user.password_hash)).await {
+            tracing::error!(
+                "wrong current password when changing password of user id = : {:?}",
+                id
+            );
+            return Err(UserError::WrongPassword);
+        }
+        check_new_password(&new_password, &new_password_confirm)?;
+        let password_hash = hash_password_blocking(new_password)
+            .await
+            .map_err(|e| UserError::PasswordHash(e.to_string()))?;
+
+        users::Entity::update(users::ActiveModel {
+            id: Set(id),
+            password_hash: Set(password_hash),
+            ..Default::default()
+        })
+        .exec(db)
+        .await?;
+
+        // 旧密码可能已经泄露，让该用户其它已登录的 session 失效；修改自己的密码时保留当前 session
+        let current = session_token().filter(|_| id == access.user_id());
+        state.sessions().remove_user(id, current.as_deref());
+
+        tracing::info!("password changed successfully for user id = : {:?}", id);
+        Ok(())
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    unreachable!("change_password should only run on the server");
+}
+
+/// Converts `err` of saving a user with `email`, telling an email used by a user in the trash apart
+/// from one used by a live user.
+#[cfg(feature = "ssr")]
+async fn user_db_error(db: &DatabaseConnection, err: DbErr, email: &str) -> UserError {
+    match UserError::from(err) {
+        UserError::DuplicateEmail => {
+            let in_trash = Users::find()
+                .filter(users::Column::Email.eq(email))
+                .filter(users::Column::DeletedAt.is_not_null())
+                .one(db)
+                .await
+                .is_ok_and(|user| user.is_some());
+            if in_trash {
+                UserError::EmailInTrash
+            } else {
+                UserError::DuplicateEmail
+            }
+        }
+        error => error,
+    }
+}
+
+/// What the logged-in user may see and modify.
+///
+/// Everybody can see and edit the users of their own workspace; owners additionally see, move and
+/// delete the users of the workspaces they own. Users outside of these workspaces are reported as
+/// [`UserError::NotFound`] so their ids are not leaked.
+#[cfg(feature = "ssr")]
+pub(crate) struct UserAccess {
+    user: users::Model,
+    owned_ws_ids: Vec<i64>,
+}
+
+#[cfg(feature = "ssr")]
+impl UserAccess {
+    /// Loads the logged-in user and the workspaces it owns; fails with [`UserError::Unauthorized`]
+    /// when nobody is logged in or the user no longer exists.
+    pub(crate) async fn load(db: &DatabaseConnection) -> Result<Self, UserError> {
+        let auth_user = require_user()?;
+        Self::for_user(db, auth_user.id).await
+    }
+
+    /// Like [`UserAccess::load`] for an already authenticated user, e.g. in plain Axum handlers.
+    pub(crate) async fn for_user(db: &DatabaseConnection, user_id: i64) -> Result<Self, UserError> {
+        // 重新读取用户，session 中的 ws_id 可能已经过期
+        let user = Users::find_by_id(user_id)
+            .filter(users::Column::DeletedAt.is_null())
+            .one(db)
+            .await?
+            .ok_or(UserError::Unauthorized)?;
+
+        let owned_ws_ids = Workspace::find()
+            .filter(workspace::Column::OwnerId.eq(user.id))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|ws| ws.id)
+            .collect();
+
+        Ok(Self { user, owned_ws_ids })
+    }
+
+    /// The id of the logged-in user.
+    pub(crate) fn user_id(&self) -> i64 {
+        self.user.id
+    }
+
+    pub(crate) fn owns(&self, ws_id: i64) -> bool {
+        self.owned_ws_ids.contains(&ws_id)
+    }
+
+    pub(crate) fn can_view(&self, ws_id: i64) -> bool {
+        ws_id == self.user.ws_id || self.owns(ws_id)
+    }
+
+    pub(crate) fn visible_ws_ids(&self) -> Vec<i64> {
+        let mut ws_ids = self.owned_ws_ids.clone();
+        if !ws_ids.contains(&self.user.ws_id) {
+            ws_ids.push(self.user.ws_id);
+        }
+        ws_ids
+    }
+
+    /// Loads user `id`, treating deleted users and users outside of the visible workspaces as missing.
+    async fn find_visible_user<C: ConnectionTrait>(
+        &self,
+        db: &C,
+        id: i64,
+    ) -> Result<users::Model, UserError> {
+        Users::find_by_id(id)
+            .filter(users::Column::DeletedAt.is_null())
+            .one(db)
+            .await?
+            .filter(|user| self.can_view(user.ws_id))
+            .ok_or(UserError::NotFound(id))
+    }
+
+    /// Loads the users `ids` that are in the visible workspaces, keyed by id; only the deleted ones
+    /// when `deleted` is set, otherwise only the others.
+    async fn find_visible_users<C: ConnectionTrait>(
+        &self,
+        db: &C,
+        ids: &[i64],
+        deleted: bool,
+    ) -> Result<HashMap<i64, users::Model>, DbErr> {
+        let deleted_condition = if deleted {
+            users::Column::DeletedAt.is_not_null()
+        } else {
+            users::Column::DeletedAt.is_null()
+        };
+        let users = Users::find()
+            .filter(users::Column::Id.is_in(ids.iter().copied()))
+            .filter(deleted_condition)
+            .filter(users::Column::WsId.is_in(self.visible_ws_ids()))
+            .all(db)
+            .await?;
+
+        Ok(users.into_iter().map(|user| (user.id, user)).collect())
+    }
+}
+
+/// Returns [`UserError::Forbidden`] and sets the `403` response status.
+#[cfg(feature = "ssr")]
+fn forbidden() -> UserError {
+    if let Some(res) = leptos::prelude::use_context::<leptos_axum::ResponseOptions>() {
+        res.set_status(axum::http::StatusCode::FORBIDDEN);
+    }
+    UserError::Forbidden
+}
+
+/// Returns [`UserError::Conflict`] with the `current` row and sets the `409` response status.
+#[cfg(feature = "ssr")]
+fn conflict(current: users::Model) -> UserError {
+    if let Some(res) = leptos::prelude::use_context::<leptos_axum::ResponseOptions>() {
+        res.set_status(axum::http::StatusCode::CONFLICT);
+    }
+    UserError::Conflict(UsersDto::from(current))
+}
+
+/// Builds the audit log entries of `actor_id` applying `action` to the `changed` users, whose
+/// previous values are in `before`.
+#[cfg(feature = "ssr")]
+fn audit_entries(
+    actor_id: i64,
+    action: AuditAction,
+    before: &HashMap<i64, users::Model>,
+    changed: &[users::Model],
+) -> Vec<audit_log::ActiveModel> {
+    changed
+        .iter()
+        .map(|user| audit_entry(actor_id, action, user.id, before.get(&user.id), Some(user)))
+        .collect()
+}
+
+/// Checks a new password against the minimum length and its confirmation.
+#[cfg(feature = "ssr")]
+fn check_new_password(password: &str, password_confirm: &str) -> Result<(), UserError> {
+    if password.is_empty() {
+        return Err(UserError::PasswordRequired);
+    }
+    if password.chars().count() < MIN_PASSWORD_LEN {
+        return Err(UserError::PasswordTooShort(MIN_PASSWORD_LEN));
+    }
+    if password != password_confirm {
+        return Err(UserError::PasswordMismatch);
+    }
+    Ok(())
+}
+
+/// Loads one page of the not deleted users in `ws_ids` matching the filters of `query`, together with the total number of matches.
+#[cfg(feature = "ssr")]
+async fn find_users_page(
+    db: &DatabaseConnection,
+    query: &UsersQuery,
+    ws_ids: &[i64],
+) -> Result<(Vec<users::Model>, u64), DbErr> {
+    let paginator = select_users(query, ws_ids).paginate(db, query.page_size());
+
+    let total = paginator.num_items().await?;
+    let users = paginator.fetch_page(query.page() - 1).await?;
+
+    Ok((users, total))
+}
+
+/// Selects the not deleted users in `ws_ids` matching the filters of `query`, in the order of
+/// `query`; the paging of `query` is ignored.
+#[cfg(feature = "ssr")]
+pub(crate) fn select_users(query: &UsersQuery, ws_ids: &[i64]) -> Select<users::Entity> {
+    let conditions = Condition::all()
+        .add(users::Column::DeletedAt.is_null())
+        .add(users::Column::WsId.is_in(ws_ids.iter().copied()))
+        .add_option(
+            query
+                .fullname
+                .as_ref()
+                .map(|fullname| users::Column::Fullname.contains(fullname)),
+        )
+        .add_option(
+            query
+                .email
+                .as_ref()
+                .map(|email| users::Column::Email.contains(email)),
+        )
+        .add_option(query.ws_id.map(|ws_id| users::Column::WsId.eq(ws_id)))
+        .add_option(query.q.as_deref().map(search_condition));
+
+    let sort_column = match query.sort_by {
+        UsersSortColumn::Id => users::Column::Id,
+        UsersSortColumn::Fullname => users::Column::Fullname,
+        UsersSortColumn::Email => users::Column::Email,
+        UsersSortColumn::CreateAt => users::Column::CreateAt,
+        UsersSortColumn::WsId => users::Column::WsId,
+    };
+    let order = match query.sort_dir {
+        SortDirection::Asc => Order::Asc,
+        SortDirection::Desc => Order::Desc,
+    };
+
+    let mut select = Users::find().filter(conditions);
+    // 搜索时最相似的排在前面
+    if let Some(q) = query.q.as_deref() {
+        select = select.order_by_desc(Expr::cust_with_values(
+            "GREATEST(similarity(users.fullname, $1), similarity(users.email, $2))",
+            [q, q],
+        ));
+    }
+
+    // 按 id 作为第二排序字段，保证分页结果稳定
+    select
+        .order_by(sort_column, order.clone())
+        .order_by(users::Column::Id, order)
+}
+
+/// Matches `q` against `fullname` and `email`, as a case-insensitive substring or by `pg_trgm`
+/// similarity.
+#[cfg(feature = "ssr")]
+fn search_condition(q: &str) -> Condition {
+    // 转义 LIKE 的通配符，让 % 和 _ 按字面匹配；Postgres 的自定义表达式用 $n 作占位符
+    let pattern = format!(
+        "%{}%",
+        q.replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_")
+    );
+    Condition::any()
+        .add(Expr::cust_with_values(
+            "users.fullname ILIKE $1",
+            [pattern.as_str()],
+        ))
+        .add(Expr::cust_with_values(
+            "users.email ILIKE $1",
+            [pattern.as_str()],
+        ))
+        .add(Expr::cust_with_values("users.fullname % $1", [q]))
+        .add(Expr::cust_with_values("users.email % $1", [q]))
+}
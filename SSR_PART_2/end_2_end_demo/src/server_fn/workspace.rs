@@ -0,0 +1,190 @@
+use crate::dto::workspace_dto::WorkspaceDto;
+use crate::errors::workspace_error::WorkspaceError;
+
+#[cfg(feature = "ssr")]
+use crate::entity::prelude::*;
+
+#[cfg(feature = "ssr")]
+use crate::entity::workspace;
+
+use leptos::server;
+
+#[cfg(feature = "ssr")]
+use leptos::prelude::expect_context;
+
+#[cfg(feature = "ssr")]
+use sea_orm::{prelude::*, QueryOrder, Set};
+
+#[cfg(feature = "ssr")]
+use crate::state::app_state::AppState;
+
+#[cfg(feature = "ssr")]
+use crate::server_fn::user::UserAccess;
+
+/// Lists the workspaces the logged-in user can see: its own workspace and the ones it owns.
+#[server]
+pub async fn get_workspaces() -> Result<Vec<WorkspaceDto>, WorkspaceError> {
+    #[cfg(feature = "ssr")]
+    {
+        let state = expect_context::<AppState>();
+        let db = state.db();
+        let access = UserAccess::load(db).await?;
+
+        let workspaces = Workspace::find()
+            .filter(workspace::Column::Id.is_in(access.visible_ws_ids()))
+            .order_by_asc(workspace::Column::Id)
+            .all(db)
+            .await?;
+
+        let workspaces_dto: Vec<_> = workspaces.into_iter().map(WorkspaceDto::from).collect();
+
+        Ok(workspaces_dto)
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    unreachable!("get_workspaces should only run on the server");
+}
+
+/// Creates a workspace owned by the logged-in user, or renames one it owns.
+///
+/// The `owner_id` of `workspace_dto` is ignored; ownership cannot be changed.
+#[server]
+pub async fn add_or_update_workspace(
+    workspace_dto: WorkspaceDto,
+) -> Result<WorkspaceDto, WorkspaceError> {
+    tracing::info!("workspace_dto: {:?}", workspace_dto);
+    #[cfg(feature = "ssr")]
+    {
+        let state = expect_context::<AppState>();
+        let db = state.db();
+        let access = UserAccess::load(db).await?;
+
+        // 去掉首尾空白后再校验和保存，长度检查才与数据库一致
+        let workspace_dto = workspace_dto.normalized();
+        let errors = workspace_dto.validate();
+        if !errors.is_empty() {
+            tracing::error!("invalid workspace_dto: {:?}", errors);
+            return Err(WorkspaceError::Validation(errors));
+        }
+
+        // 更新
+        if workspace_dto.id > -1 {
+            check_owner(&access, workspace_dto.id)?;
+
+            let rt = workspace::Entity::update(workspace::ActiveModel {
+                id: Set(workspace_dto.id),
+                name: Set(workspace_dto.name),
+                ..Default::default()
+            })
+            .exec(db)
+            .await;
+
+            match rt {
+                Ok(ws) => {
+                    tracing::info!(
+                        "workspace updated successfully with id = : {:?}, name = : {:?}",
+                        ws.id,
+                        ws.name
+                    );
+                    // 改名后打开的列表和 SSE 监听也要刷新
+                    state.publish_workspace_change();
+                    Ok(ws.into())
+                }
+                Err(DbErr::RecordNotUpdated) => {
+                    tracing::error!("Workspace id: {} not found", workspace_dto.id);
+                    Err(WorkspaceError::NotFound(workspace_dto.id))
+                }
+                Err(e) => {
+                    tracing::error!("error updating workspace: {:?}", e);
+                    Err(e.into())
+                }
+            }
+        }
+        // 新增
+        else {
+            let new_workspace = workspace::ActiveModel {
+                name: Set(workspace_dto.name),
+                owner_id: Set(access.user_id()),
+                ..Default::default()
+            };
+
+            let rt = new_workspace.insert(db).await;
+
+            match rt {
+                Ok(ws) => {
+                    tracing::info!(
+                        "workspace created successfully with id = : {:?} and name = : {:?}",
+                        ws.id,
+                        ws.name
+                    );
+                    state.publish_workspace_change();
+                    Ok(ws.into())
+                }
+                Err(e) => {
+                    tracing::error!("error creating workspace: {:?}", e);
+                    Err(e.into())
+                }
+            }
+        }
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    unreachable!("add_or_update_workspace should only run on the server");
+}
+
+/// Deletes workspace `id`; only its owner may, and only once it has no users.
+#[server]
+pub async fn delete_workspace(id: i64) -> Result<(), WorkspaceError> {
+    #[cfg(feature = "ssr")]
+    {
+        let state = expect_context::<AppState>();
+        let db = state.db();
+        let access = UserAccess::load(db).await?;
+        check_owner(&access, id)?;
+
+        let rt = workspace::Entity::delete_by_id(id).exec(db).await;
+
+        match rt {
+            Ok(deleted) => {
+                if deleted.rows_affected > 0 {
+                    tracing::info!("Workspace was deleted successfully with id = : {:?}!", id);
+                    state.publish_workspace_change();
+                    Ok(())
+                } else {
+                    tracing::error!("When delete the workspace, with id = : {:?} not found", id);
+                    Err(WorkspaceError::NotFound(id))
+                }
+            }
+            Err(e) => {
+                tracing::error!("error deleting workspace: {:?}", e);
+                Err(e.into())
+            }
+        }
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    unreachable!("delete_workspace should only run on the server");
+}
+
+/// Fails unless the logged-in user owns workspace `ws_id`: with [`WorkspaceError::Forbidden`] and a
+/// `403` response status for its own workspace, and as [`WorkspaceError::NotFound`] for workspaces it
+/// cannot see, so their ids are not leaked.
+#[cfg(feature = "ssr")]
+fn check_owner(access: &UserAccess, ws_id: i64) -> Result<(), WorkspaceError> {
+    if access.owns(ws_id) {
+        return Ok(());
+    }
+    if !access.can_view(ws_id) {
+        return Err(WorkspaceError::NotFound(ws_id));
+    }
+
+    tracing::error!(
+        "user id = : {:?} is not allowed to change workspace id = : {:?}",
+        access.user_id(),
+        ws_id
+    );
+    if let Some(res) = leptos::prelude::use_context::<leptos_axum::ResponseOptions>() {
+        res.set_status(axum::http::StatusCode::FORBIDDEN);
+    }
+    Err(WorkspaceError::Forbidden)
+}
@@ -1,21 +1,126 @@
+use std::sync::Arc;
+
 use sea_orm::DatabaseConnection;
+use serde::Serialize;
+use server_common::metrics::Metrics;
+use tokio::sync::{broadcast, watch};
+
+use crate::auth::session::SessionStore;
+use crate::dto::user_event::UserChange;
+
+/// Number of user events kept for listeners that fall behind before they have to resync.
+const USER_EVENTS_CAPACITY: usize = 256;
 
 /// Application state shared across all request handlers.
 ///
 /// Contains database connection pool and other shared resources.
 #[derive(Debug, Clone)]
 pub struct AppState {
-    pub db: DatabaseConnection,
+    /// Shared through an `Arc`, as `DatabaseConnection` is not `Clone` with SeaORM's `mock` feature.
+    pub db: Arc<DatabaseConnection>,
+    pub sessions: SessionStore,
+    pub user_events: broadcast::Sender<UserChange>,
+    /// Marked changed whenever a workspace is created or deleted.
+    pub workspace_changes: watch::Sender<()>,
+    pub metrics: Metrics,
+    /// `true` once the server stopped accepting requests and waits for the in-flight ones.
+    pub draining: watch::Sender<bool>,
+}
+
+/// Connections of the database pool.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct PoolStats {
+    /// Open connections, in use or idle.
+    pub size: u32,
+    pub idle: u32,
+}
+
+impl PoolStats {
+    pub fn in_use(&self) -> u32 {
+        self.size.saturating_sub(self.idle)
+    }
 }
 
 impl AppState {
-    /// Creates a new application state with the given database connection.
+    /// Creates a new application state with the given database connection, an empty session store,
+    /// user and workspace change channels without listeners and empty metrics, not draining.
     pub fn new(db: DatabaseConnection) -> Self {
-        Self { db }
+        let (user_events, _) = broadcast::channel(USER_EVENTS_CAPACITY);
+        Self {
+            db: Arc::new(db),
+            sessions: SessionStore::default(),
+            user_events,
+            workspace_changes: watch::Sender::new(()),
+            metrics: Metrics::new(),
+            draining: watch::Sender::new(false),
+        }
     }
 
     /// Returns a reference to the database connection.
     pub fn db(&self) -> &DatabaseConnection {
         &self.db
     }
+
+    /// Returns the connections of the database pool, `None` for a mock database.
+    pub fn pool_stats(&self) -> Option<PoolStats> {
+        match self.db() {
+            DatabaseConnection::SqlxPostgresPoolConnection(_) => {
+                let pool = self.db.get_postgres_connection_pool();
+                Some(PoolStats {
+                    size: pool.size(),
+                    idle: pool.num_idle() as u32,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the metrics recorded by the [`Metrics::layer`] wrapped around the router.
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    /// Returns the store of logged-in sessions.
+    pub fn sessions(&self) -> &SessionStore {
+        &self.sessions
+    }
+
+    /// Sends `change` to every open `/users/events` stream.
+    pub fn publish_user_event(&self, change: UserChange) {
+        // 没有监听者时发送会失败，忽略即可
+        let _ = self.user_events.send(change);
+    }
+
+    /// Subscribes to the user changes published from now on.
+    pub fn subscribe_user_events(&self) -> broadcast::Receiver<UserChange> {
+        self.user_events.subscribe()
+    }
+
+    /// Tells the open `/users/events` streams that workspaces were created or deleted, so they
+    /// reload which workspaces their user can see.
+    pub fn publish_workspace_change(&self) {
+        self.workspace_changes.send_replace(());
+    }
+
+    /// Subscribes to the workspace changes published from now on.
+    pub fn subscribe_workspace_changes(&self) -> watch::Receiver<()> {
+        self.workspace_changes.subscribe()
+    }
+
+    /// Marks the server as shutting down: `/readyz` fails and open `/users/events` streams end.
+    pub fn start_draining(&self) {
+        self.draining.send_replace(true);
+    }
+
+    /// Returns whether [`AppState::start_draining`] was called.
+    pub fn is_draining(&self) -> bool {
+        *self.draining.borrow()
+    }
+
+    /// Resolves once [`AppState::start_draining`] is called.
+    pub async fn draining_started(&self) {
+        let mut draining = self.draining.subscribe();
+        // 发送端就在 self 中，不会关闭
+        let _ = draining.wait_for(|draining| *draining).await;
+    }
 }
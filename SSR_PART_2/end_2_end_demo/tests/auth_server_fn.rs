@@ -0,0 +1,157 @@
+//! Integration tests for the session server functions in `server_fn/auth.rs` and the login
+//! requirement of the user server functions.
+//!
+//! Run with `cargo test --features ssr`.
+
+#![cfg(feature = "ssr")]
+
+mod common;
+
+use axum::http::header::{COOKIE, SET_COOKIE};
+use axum::http::{Request, StatusCode};
+use common::{super_user, user_model, TestContext};
+use end_2_end_demo::auth::password::hash_password;
+use end_2_end_demo::dto::users_dto::UsersDto;
+use end_2_end_demo::entity::users;
+use end_2_end_demo::errors::user_error::UserError;
+use end_2_end_demo::server_fn::auth::{current_user, login, logout};
+use end_2_end_demo::server_fn::user::{add_or_update_users, delete_users};
+use end_2_end_demo::state::app_state::AppState;
+use leptos::prelude::{expect_context, provide_context};
+use leptos_axum::ResponseOptions;
+
+fn response_status() -> Option<StatusCode> {
+    expect_context::<ResponseOptions>().0.read().unwrap().status
+}
+
+fn set_cookie() -> Option<String> {
+    expect_context::<ResponseOptions>()
+        .0
+        .read()
+        .unwrap()
+        .headers
+        .get(SET_COOKIE)
+        .map(|value| value.to_str().unwrap().to_string())
+}
+
+#[tokio::test]
+async fn anonymous_delete_is_unauthorized() {
+    let ctx = TestContext::mock(TestContext::mock_db());
+
+    assert_eq!(delete_users(7).await, Err(UserError::Unauthorized));
+    assert_eq!(response_status(), Some(StatusCode::UNAUTHORIZED));
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn anonymous_add_or_update_is_unauthorized() {
+    let ctx = TestContext::mock(TestContext::mock_db());
+
+    let rt = add_or_update_users(
+        UsersDto::new(
+            7,
+            "Bobby".to_string(),
+            None,
+            "bobby@none.co".to_string(),
+            None,
+            0,
+        ),
+        None,
+        None,
+    )
+    .await;
+
+    assert_eq!(rt, Err(UserError::Unauthorized));
+    assert_eq!(response_status(), Some(StatusCode::UNAUTHORIZED));
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn login_sets_session_cookie() {
+    let mut user = user_model(7, "Bobby", "bobby@none.co", 0);
+    user.password_hash = hash_password("correct horse").unwrap();
+    let ctx = TestContext::mock(TestContext::mock_db().append_query_results([vec![user]]));
+
+    let auth_user = login("bobby@none.co".to_string(), "correct horse".to_string())
+        .await
+        .unwrap();
+    assert_eq!(auth_user.id, 7);
+
+    let cookie = set_cookie().unwrap();
+    let token = cookie
+        .strip_prefix("session_id=")
+        .and_then(|rest| rest.split(';').next())
+        .unwrap();
+    assert!(cookie.contains("HttpOnly"));
+    assert_eq!(
+        expect_context::<AppState>().sessions().get(token),
+        Some(auth_user)
+    );
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn login_ends_the_session_of_the_request() {
+    let mut user = user_model(7, "Bobby", "bobby@none.co", 0);
+    user.password_hash = hash_password("correct horse").unwrap();
+    let ctx = TestContext::mock(TestContext::mock_db().append_query_results([vec![user]]));
+
+    // 登录前请求已经带着一个 session cookie，例如被别人设置的
+    let old_token = expect_context::<AppState>().sessions().create(super_user());
+    let (parts, _) = Request::builder()
+        .header(COOKIE, format!("session_id={}", old_token))
+        .body(())
+        .unwrap()
+        .into_parts();
+    provide_context(parts);
+
+    let auth_user = login("bobby@none.co".to_string(), "correct horse".to_string())
+        .await
+        .unwrap();
+    assert_eq!(
+        expect_context::<AppState>().sessions().get(&old_token),
+        None
+    );
+    assert!(!set_cookie().unwrap().contains(&old_token));
+    assert_eq!(auth_user.id, 7);
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn login_with_wrong_password_or_unknown_email_fails() {
+    let mut user = user_model(7, "Bobby", "bobby@none.co", 0);
+    user.password_hash = hash_password("correct horse").unwrap();
+    let ctx = TestContext::mock(
+        TestContext::mock_db().append_query_results([vec![user], Vec::<users::Model>::new()]),
+    );
+
+    let rt = login("bobby@none.co".to_string(), "wrong horse".to_string()).await;
+    assert_eq!(rt, Err(UserError::InvalidCredentials));
+
+    let rt = login("nobody@none.co".to_string(), "correct horse".to_string()).await;
+    assert_eq!(rt, Err(UserError::InvalidCredentials));
+
+    assert_eq!(set_cookie(), None);
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn current_user_and_logout() {
+    let ctx = TestContext::mock(TestContext::mock_db());
+    assert_eq!(current_user().await, Ok(None));
+
+    let token = expect_context::<AppState>().sessions().create(super_user());
+    let (parts, _) = Request::builder()
+        .header(COOKIE, format!("theme=dark; session_id={}", token))
+        .body(())
+        .unwrap()
+        .into_parts();
+    provide_context(parts);
+    ctx.login_as(super_user());
+    assert_eq!(current_user().await, Ok(Some(super_user())));
+
+    assert_eq!(logout().await, Ok(()));
+    assert_eq!(expect_context::<AppState>().sessions().get(&token), None);
+    assert!(set_cookie().unwrap().contains("Max-Age=0"));
+    ctx.cleanup().await;
+}
@@ -0,0 +1,204 @@
+//! Shared harness for the server function integration tests.
+//!
+//! Server functions read the [`AppState`] from the reactive context, so every test builds a
+//! [`TestContext`] which owns a reactive [`Owner`] with the state provided to it. The database is
+//! either a SeaORM [`MockDatabase`] or a throwaway schema in the local Postgres at
+//! `TEST_DATABASE_URL` that is migrated up on creation and dropped by [`TestContext::cleanup`].
+
+#![allow(dead_code)]
+
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use end_2_end_demo::auth::session::CurrentUser;
+use end_2_end_demo::dto::auth_user::AuthUser;
+use end_2_end_demo::entity::sea_orm_active_enums::Gender;
+use end_2_end_demo::entity::{users, workspace};
+use end_2_end_demo::migration::{Migrator, MigratorTrait};
+use end_2_end_demo::state::app_state::AppState;
+use leptos::prelude::{provide_context, Owner};
+use leptos_axum::ResponseOptions;
+use sea_orm::{
+    ConnectOptions, ConnectionTrait, Database, DatabaseConnection, DbBackend, MockDatabase,
+    MockExecResult,
+};
+use tokio::sync::OnceCell;
+
+static SCHEMA_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+/// Created once per test run, see [`TestContext::postgres`].
+static PG_TRGM: OnceCell<()> = OnceCell::const_new();
+
+pub struct TestContext {
+    state: AppState,
+    /// Throwaway Postgres schema, `None` for the mock database
+    schema: Option<(DatabaseConnection, String)>,
+    owner: Owner,
+}
+
+impl TestContext {
+    /// Creates a context backed by a mock database with the given prepared results.
+    pub fn mock(mock_db: MockDatabase) -> Self {
+        Self::new(mock_db.into_connection(), None)
+    }
+
+    /// Creates an empty Postgres-flavoured mock database to append results to.
+    pub fn mock_db() -> MockDatabase {
+        MockDatabase::new(DbBackend::Postgres)
+    }
+
+    /// Creates a context backed by a freshly migrated schema in the Postgres database at
+    /// `TEST_DATABASE_URL`.
+    ///
+    /// The tests using it are `#[ignore]`d and run with `--ignored`, so a missing variable fails them
+    /// instead of letting them pass without running.
+    pub async fn postgres() -> Self {
+        Self::postgres_from("").await
+    }
+
+    /// Like [`TestContext::postgres`], but runs `sql` in the schema before migrating it, e.g. the
+    /// old `sql/init.sql`.
+    pub async fn postgres_from(sql: &str) -> Self {
+        let url = std::env::var("TEST_DATABASE_URL")
+            .expect("TEST_DATABASE_URL must point at a Postgres database to run the ignored tests");
+        let schema = format!(
+            "test_{}_{}",
+            std::process::id(),
+            SCHEMA_COUNTER.fetch_add(1, Ordering::SeqCst)
+        );
+
+        let admin = Database::connect(&url)
+            .await
+            .expect("Failed to connect to TEST_DATABASE_URL");
+        // pg_trgm 只能装在一个 schema 中：装到 public，每个测试 schema 的 search_path 都包含它
+        PG_TRGM
+            .get_or_init(|| async {
+                admin
+                    .execute_unprepared("CREATE EXTENSION IF NOT EXISTS pg_trgm SCHEMA public")
+                    .await
+                    .expect("Failed to create the pg_trgm extension");
+            })
+            .await;
+        admin
+            .execute_unprepared(&format!("CREATE SCHEMA \"{}\"", schema))
+            .await
+            .expect("Failed to create test schema");
+
+        let mut options = ConnectOptions::new(url);
+        options
+            .max_connections(2)
+            .sqlx_logging(false)
+            .set_schema_search_path(format!("{},public", schema));
+        let db = Database::connect(options)
+            .await
+            .expect("Failed to connect to test schema");
+        if !sql.is_empty() {
+            db.execute_unprepared(sql)
+                .await
+                .expect("Failed to set up test schema");
+        }
+        Migrator::up(&db, None)
+            .await
+            .expect("Failed to migrate test schema");
+
+        Self::new(db, Some((admin, schema)))
+    }
+
+    fn new(db: DatabaseConnection, schema: Option<(DatabaseConnection, String)>) -> Self {
+        let owner = Owner::new();
+        owner.set();
+        let state = AppState::new(db);
+        provide_context(state.clone());
+        provide_context(ResponseOptions::default());
+
+        Self {
+            state,
+            schema,
+            owner,
+        }
+    }
+
+    /// The database the server functions of this context use.
+    pub fn db(&self) -> &DatabaseConnection {
+        self.state.db()
+    }
+
+    /// The state the server functions of this context read.
+    pub fn state(&self) -> &AppState {
+        &self.state
+    }
+
+    /// Makes the following server function calls run as `user`.
+    pub fn login_as(&self, user: AuthUser) {
+        provide_context(CurrentUser(Some(user)));
+    }
+
+    /// Drops the throwaway schema, if any.
+    pub async fn cleanup(self) {
+        if let Some((admin, schema)) = self.schema {
+            self.state.db().close_by_ref().await.ok();
+            admin
+                .execute_unprepared(&format!("DROP SCHEMA \"{}\" CASCADE", schema))
+                .await
+                .expect("Failed to drop test schema");
+        }
+    }
+}
+
+/// Builds a `users` row as the database would return it.
+pub fn user_model(id: i64, fullname: &str, email: &str, ws_id: i64) -> users::Model {
+    users::Model {
+        id,
+        fullname: fullname.to_string(),
+        gender: Some(Gender::Male),
+        email: email.to_string(),
+        password_hash: String::new(),
+        create_at: Some(chrono::Utc::now().fixed_offset()),
+        ws_id,
+        deleted_at: None,
+        version: 0,
+    }
+}
+
+/// The seeded super user, owner of workspace `0`.
+pub fn super_user() -> AuthUser {
+    AuthUser::new(0, "Super".to_string(), "super@none.com".to_string(), 0)
+}
+
+/// Creates a mock database prepared with the rows every access-checked user server function loads
+/// first: the logged-in `caller` and the workspaces it owns.
+pub fn mock_access(caller: &AuthUser, owned_ws_ids: &[i64]) -> MockDatabase {
+    TestContext::mock_db()
+        .append_query_results([vec![user_model(
+            caller.id,
+            &caller.fullname,
+            &caller.email,
+            caller.ws_id,
+        )]])
+        .append_query_results([owned_ws_ids
+            .iter()
+            .map(|&id| workspace_model(id, caller.id))
+            .collect::<Vec<_>>()])
+}
+
+/// Builds a `workspace` row as the database would return it.
+pub fn workspace_model(id: i64, owner_id: i64) -> workspace::Model {
+    workspace::Model {
+        id,
+        name: format!("ws-{}", id),
+        owner_id,
+        create_at: Some(chrono::Utc::now().fixed_offset()),
+    }
+}
+
+/// A user of workspace `0` that owns no workspace.
+pub fn member_user() -> AuthUser {
+    AuthUser::new(7, "Bobby".to_string(), "bobby@none.co".to_string(), 0)
+}
+
+/// Result of the `INSERT` of the audit log entries written by a successful mutation.
+pub fn audit_log_written() -> MockExecResult {
+    MockExecResult {
+        last_insert_id: 0,
+        rows_affected: 1,
+    }
+}
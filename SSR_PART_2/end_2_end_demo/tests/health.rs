@@ -0,0 +1,81 @@
+//! Integration tests for the `/healthz` and `/readyz` routes in `handlers/health.rs`.
+//!
+//! Run with `cargo test --features ssr`.
+
+#![cfg(feature = "ssr")]
+
+mod common;
+
+use std::collections::BTreeMap;
+
+use axum::body::{to_bytes, Body};
+use axum::http::{Request, StatusCode};
+use common::TestContext;
+use end_2_end_demo::handlers::health;
+use end_2_end_demo::state::app_state::AppState;
+use leptos::prelude::expect_context;
+use sea_orm::Value;
+use serde_json::Value as Json;
+use tower::ServiceExt;
+
+async fn get(state: AppState, uri: &str) -> (StatusCode, Json) {
+    let response = health::router(state)
+        .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[tokio::test]
+async fn healthz_is_ok() {
+    let ctx = TestContext::mock(TestContext::mock_db());
+
+    let (status, body) = get(expect_context::<AppState>(), "/healthz").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["status"], "ok");
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn readyz_reports_database_version() {
+    let version = BTreeMap::from([("version", Value::from("PostgreSQL 16.2"))]);
+    let ctx = TestContext::mock(TestContext::mock_db().append_query_results([[version]]));
+
+    let (status, body) = get(expect_context::<AppState>(), "/readyz").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["ready"], true);
+    assert_eq!(body["database"]["reachable"], true);
+    assert_eq!(body["database"]["version"], "PostgreSQL 16.2");
+    assert_eq!(body["build"]["name"], "end_2_end_demo");
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn readyz_is_unavailable_without_database() {
+    // 没有准备查询结果，查询数据库版本会失败
+    let ctx = TestContext::mock(TestContext::mock_db());
+
+    let (status, body) = get(expect_context::<AppState>(), "/readyz").await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["ready"], false);
+    assert_eq!(body["database"]["reachable"], false);
+    assert!(body["database"]["error"].is_string());
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn readyz_is_unavailable_while_draining() {
+    let version = BTreeMap::from([("version", Value::from("PostgreSQL 16.2"))]);
+    let ctx = TestContext::mock(TestContext::mock_db().append_query_results([[version]]));
+    let state = expect_context::<AppState>();
+
+    state.start_draining();
+    let (status, body) = get(state, "/readyz").await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["ready"], false);
+    assert_eq!(body["draining"], true);
+    assert_eq!(body["database"]["reachable"], true);
+    ctx.cleanup().await;
+}
@@ -0,0 +1,85 @@
+//! Integration tests for the `/metrics` route in `handlers/metrics.rs` and the layer recording
+//! the metrics in `server_common::metrics`.
+//!
+//! Run with `cargo test --features ssr`.
+
+#![cfg(feature = "ssr")]
+
+mod common;
+
+use axum::body::{to_bytes, Body};
+use axum::http::header::CONTENT_TYPE;
+use axum::http::{Method, Request, StatusCode};
+use axum::routing::{get, post};
+use axum::Router;
+use common::TestContext;
+use end_2_end_demo::handlers::metrics;
+use end_2_end_demo::server_fn::user::MoveUsers;
+use end_2_end_demo::state::app_state::AppState;
+use leptos::prelude::expect_context;
+use leptos::server_fn::ServerFn;
+use tower::ServiceExt;
+
+async fn send(app: &Router, method: Method, uri: &str) -> (StatusCode, String) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn requests_and_server_fn_calls_are_counted() {
+    let ctx = TestContext::mock(TestContext::mock_db());
+    let state = expect_context::<AppState>();
+    let app = Router::new()
+        .route("/users/{id}", get(|| async { "ok" }))
+        .route(
+            "/api/{*fn_name}",
+            post(|| async { StatusCode::INTERNAL_SERVER_ERROR }),
+        )
+        .merge(metrics::router(state.clone()))
+        .layer(state.metrics().layer());
+
+    send(&app, Method::GET, "/users/1").await;
+    send(&app, Method::GET, "/users/2").await;
+    send(&app, Method::POST, MoveUsers::PATH).await;
+    // 没有注册的 server fn 不会产生新的 label
+    send(&app, Method::POST, "/api/random1234567890").await;
+    send(&app, Method::POST, "/api/other").await;
+
+    let response = app
+        .clone()
+        .oneshot(Request::get("/metrics").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers()[CONTENT_TYPE]
+        .to_str()
+        .unwrap()
+        .starts_with("text/plain"));
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let body = String::from_utf8(body.to_vec()).unwrap();
+
+    // 按路由模板而不是实际路径统计
+    assert!(
+        body.contains(r#"http_requests_total{method="GET",route="/users/{id}",status="200"} 2"#)
+    );
+    assert!(body.contains(
+        r#"http_request_duration_seconds_count{method="GET",route="/users/{id}",status="200"} 2"#
+    ));
+    assert!(body.contains(r#"server_fn_calls_total{server_fn="move_users"} 1"#));
+    assert!(body.contains(r#"server_fn_errors_total{server_fn="move_users"} 1"#));
+    assert!(body.contains(r#"server_fn_calls_total{server_fn="unmatched"} 2"#));
+    assert!(!body.contains("random"));
+    ctx.cleanup().await;
+}
@@ -0,0 +1,43 @@
+//! Integration tests for the migrations in `src/migration`.
+//!
+//! Run with `TEST_DATABASE_URL=... cargo test --features ssr -- --ignored`, see
+//! `tests/user_server_fn.rs`.
+
+#![cfg(feature = "ssr")]
+
+mod common;
+
+use common::TestContext;
+use end_2_end_demo::entity::{users, workspace};
+use end_2_end_demo::migration::{Migrator, MigratorTrait};
+use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter};
+
+#[tokio::test]
+#[ignore = "needs TEST_DATABASE_URL"]
+async fn postgres_migrate_up_on_init_sql_database() {
+    // postgres_from 在执行 init.sql 之后运行 migrate up
+    let ctx = TestContext::postgres_from(include_str!("../sql/init.sql")).await;
+
+    assert!(Migrator::get_pending_migrations(ctx.db())
+        .await
+        .unwrap()
+        .is_empty());
+    // the super user and the 9 seed users, none of them twice
+    assert_eq!(users::Entity::find().count(ctx.db()).await.unwrap(), 10);
+    assert_eq!(
+        users::Entity::find()
+            .filter(users::Column::WsId.ne(0))
+            .count(ctx.db())
+            .await
+            .unwrap(),
+        0
+    );
+    assert_eq!(workspace::Entity::find().count(ctx.db()).await.unwrap(), 1);
+
+    // the migrations can be rolled back and applied again
+    Migrator::down(ctx.db(), None).await.unwrap();
+    Migrator::up(ctx.db(), None).await.unwrap();
+    assert_eq!(users::Entity::find().count(ctx.db()).await.unwrap(), 10);
+
+    ctx.cleanup().await;
+}
@@ -0,0 +1,202 @@
+//! Integration tests for the `/users/events` route in `handlers/user_events.rs`.
+//!
+//! Run with `cargo test --features ssr`.
+
+#![cfg(feature = "ssr")]
+
+mod common;
+
+use axum::body::Body;
+use axum::http::header::COOKIE;
+use axum::http::{Request, Response, StatusCode};
+use common::{member_user, mock_access, user_model, workspace_model, TestContext};
+use end_2_end_demo::dto::auth_user::AuthUser;
+use end_2_end_demo::dto::user_event::{UserChange, UserEvent};
+use end_2_end_demo::dto::users_dto::UsersDto;
+use end_2_end_demo::entity::workspace;
+use end_2_end_demo::handlers::user_events;
+use end_2_end_demo::state::app_state::AppState;
+use futures::StreamExt;
+use leptos::prelude::expect_context;
+use tower::ServiceExt;
+
+async fn get_events(state: AppState, token: Option<&str>) -> Response<Body> {
+    let mut request = Request::builder().uri("/users/events");
+    if let Some(token) = token {
+        request = request.header(COOKIE, format!("session_id={}", token));
+    }
+    user_events::router(state)
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+fn user(id: i64, ws_id: i64) -> UsersDto {
+    UsersDto::new(
+        id,
+        format!("User {}", id),
+        None,
+        format!("user{}@none.co", id),
+        None,
+        ws_id,
+    )
+}
+
+/// Reads the next server-sent event and parses its `data` field.
+async fn next_event(body: &mut axum::body::BodyDataStream) -> UserEvent {
+    let chunk = body.next().await.unwrap().unwrap();
+    let message = String::from_utf8(chunk.to_vec()).unwrap();
+    let data = message
+        .lines()
+        .find_map(|line| line.strip_prefix("data: "))
+        .unwrap_or_else(|| panic!("no data in {:?}", message));
+    serde_json::from_str(data).unwrap()
+}
+
+#[tokio::test]
+async fn events_without_session_are_unauthorized() {
+    let ctx = TestContext::mock(TestContext::mock_db());
+
+    let response = get_events(expect_context::<AppState>(), None).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn events_are_restricted_to_visible_workspaces() {
+    let ctx = TestContext::mock(mock_access(&member_user(), &[]));
+    let state = expect_context::<AppState>();
+    let token = state.sessions().create(member_user());
+
+    let response = get_events(state.clone(), Some(&token)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let mut body = response.into_body().into_data_stream();
+
+    // the member can only see workspace 0, users moved elsewhere disappear from its list
+    state.publish_user_event(UserChange::Changed(vec![
+        (user(1, 0), Some(0)),
+        (user(2, 5), Some(0)),
+    ]));
+    state.publish_user_event(UserChange::Removed(vec![(3, 0)]));
+
+    assert_eq!(
+        next_event(&mut body).await,
+        UserEvent::Changed(vec![user(1, 0)])
+    );
+    assert_eq!(next_event(&mut body).await, UserEvent::Removed(vec![2]));
+    assert_eq!(next_event(&mut body).await, UserEvent::Removed(vec![3]));
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn events_of_other_workspaces_are_not_sent() {
+    // a user of workspace 5 that owns no workspace
+    let other = AuthUser::new(9, "Dora".to_string(), "dora@none.co".to_string(), 5);
+    let ctx = TestContext::mock(
+        mock_access(&member_user(), &[])
+            .append_query_results([vec![user_model(9, "Dora", "dora@none.co", 5)]])
+            .append_query_results([Vec::<workspace::Model>::new()]),
+    );
+    let state = expect_context::<AppState>();
+    let member_token = state.sessions().create(member_user());
+    let other_token = state.sessions().create(other);
+
+    let mut member = get_events(state.clone(), Some(&member_token))
+        .await
+        .into_body()
+        .into_data_stream();
+    let mut other = get_events(state.clone(), Some(&other_token))
+        .await
+        .into_body()
+        .into_data_stream();
+
+    state.publish_user_event(UserChange::Changed(vec![
+        (user(1, 0), Some(0)),
+        (user(2, 5), Some(0)),
+    ]));
+    state.publish_user_event(UserChange::Removed(vec![(3, 0), (4, 5)]));
+    // the last event of each listener shows that nothing else was sent before it
+    state.publish_user_event(UserChange::Changed(vec![(user(10, 0), None)]));
+    state.publish_user_event(UserChange::Changed(vec![(user(11, 5), None)]));
+
+    assert_eq!(
+        next_event(&mut member).await,
+        UserEvent::Changed(vec![user(1, 0)])
+    );
+    assert_eq!(next_event(&mut member).await, UserEvent::Removed(vec![2]));
+    assert_eq!(next_event(&mut member).await, UserEvent::Removed(vec![3]));
+    assert_eq!(
+        next_event(&mut member).await,
+        UserEvent::Changed(vec![user(10, 0)])
+    );
+
+    // user 2 moved into workspace 5; users 1 and 3 were never visible to this listener
+    assert_eq!(
+        next_event(&mut other).await,
+        UserEvent::Changed(vec![user(2, 5)])
+    );
+    assert_eq!(next_event(&mut other).await, UserEvent::Removed(vec![4]));
+    assert_eq!(
+        next_event(&mut other).await,
+        UserEvent::Changed(vec![user(11, 5)])
+    );
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn events_end_when_draining() {
+    let ctx = TestContext::mock(mock_access(&member_user(), &[]));
+    let state = expect_context::<AppState>();
+    let token = state.sessions().create(member_user());
+
+    let response = get_events(state.clone(), Some(&token)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let mut body = response.into_body().into_data_stream();
+
+    // 服务关闭时事件流结束，连接不会拖到关闭超时
+    state.start_draining();
+    assert!(body.next().await.is_none());
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn events_end_after_logout() {
+    let ctx = TestContext::mock(mock_access(&member_user(), &[]));
+    let state = expect_context::<AppState>();
+    let token = state.sessions().create(member_user());
+
+    let response = get_events(state.clone(), Some(&token)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let mut body = response.into_body().into_data_stream();
+
+    // 退出登录后不再发送其它用户的信息
+    state.sessions().remove(&token);
+    state.publish_user_event(UserChange::Changed(vec![(user(1, 0), None)]));
+    assert!(body.next().await.is_none());
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn events_follow_workspace_changes() {
+    // the member creates workspace 5 after opening the stream
+    let ctx = TestContext::mock(
+        mock_access(&member_user(), &[])
+            .append_query_results([vec![user_model(7, "Bobby", "bobby@none.co", 0)]])
+            .append_query_results([vec![workspace_model(5, 7)]]),
+    );
+    let state = expect_context::<AppState>();
+    let token = state.sessions().create(member_user());
+
+    let mut body = get_events(state.clone(), Some(&token))
+        .await
+        .into_body()
+        .into_data_stream();
+
+    state.publish_workspace_change();
+    state.publish_user_event(UserChange::Changed(vec![(user(2, 5), Some(0))]));
+    assert_eq!(
+        next_event(&mut body).await,
+        UserEvent::Changed(vec![user(2, 5)])
+    );
+    ctx.cleanup().await;
+}
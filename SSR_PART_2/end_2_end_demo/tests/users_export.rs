@@ -0,0 +1,106 @@
+//! Integration tests for the `/users/export.*` routes in `handlers/users_export.rs`.
+//!
+//! Run with `cargo test --features ssr`.
+
+#![cfg(feature = "ssr")]
+
+mod common;
+
+use axum::body::{to_bytes, Body};
+use axum::http::header::COOKIE;
+use axum::http::{Request, StatusCode};
+use common::{mock_access, super_user, user_model, TestContext};
+use end_2_end_demo::entity::users;
+use end_2_end_demo::handlers::users_export;
+use end_2_end_demo::state::app_state::AppState;
+use leptos::prelude::expect_context;
+use tower::ServiceExt;
+
+async fn get(state: AppState, uri: &str, token: Option<&str>) -> (StatusCode, String) {
+    let mut request = Request::builder().uri(uri);
+    if let Some(token) = token {
+        request = request.header(COOKIE, format!("session_id={}", token));
+    }
+    let response = users_export::router(state)
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+/// One page of users followed by the empty page that ends the export.
+fn two_users() -> [Vec<users::Model>; 2] {
+    let mut bobby = user_model(7, "Bobby", "bobby@none.co", 0);
+    bobby.gender = None;
+    [
+        vec![bobby, user_model(8, "Carol, Jr.", "carol@none.co", 0)],
+        vec![],
+    ]
+}
+
+#[tokio::test]
+async fn export_without_session_is_unauthorized() {
+    let ctx = TestContext::mock(TestContext::mock_db());
+
+    let (status, _) = get(expect_context::<AppState>(), "/users/export.csv", None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, _) = get(
+        expect_context::<AppState>(),
+        "/users/export.json",
+        Some("bad"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn export_csv() {
+    let ctx = TestContext::mock(mock_access(&super_user(), &[0]).append_query_results(two_users()));
+    let state = expect_context::<AppState>();
+    let token = state.sessions().create(super_user());
+
+    let (status, body) = get(state, "/users/export.csv?email=none.co", Some(&token)).await;
+
+    assert_eq!(status, StatusCode::OK);
+    let lines: Vec<_> = body.lines().collect();
+    assert_eq!(lines[0], "id,fullname,gender,email,create_at,ws_id");
+    assert!(lines[1].starts_with("7,Bobby,,bobby@none.co,"));
+    assert!(lines[2].starts_with("8,\"Carol, Jr.\",Male,carol@none.co,"));
+    assert_eq!(lines.len(), 3);
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn export_json() {
+    let ctx = TestContext::mock(mock_access(&super_user(), &[0]).append_query_results(two_users()));
+    let state = expect_context::<AppState>();
+    let token = state.sessions().create(super_user());
+
+    let (status, body) = get(state, "/users/export.json", Some(&token)).await;
+
+    assert_eq!(status, StatusCode::OK);
+    let users: Vec<serde_json::Value> = serde_json::from_str(&body).unwrap();
+    assert_eq!(users.len(), 2);
+    assert_eq!(users[1]["fullname"], "Carol, Jr.");
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn export_json_without_users_is_an_empty_array() {
+    let ctx = TestContext::mock(
+        mock_access(&super_user(), &[0]).append_query_results([Vec::<users::Model>::new()]),
+    );
+    let state = expect_context::<AppState>();
+    let token = state.sessions().create(super_user());
+
+    let (status, body) = get(state, "/users/export.json", Some(&token)).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, "[]");
+    ctx.cleanup().await;
+}
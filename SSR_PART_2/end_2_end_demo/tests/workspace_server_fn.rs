@@ -0,0 +1,154 @@
+//! Integration tests for the workspace server functions in `server_fn/workspace.rs`.
+//!
+//! Run with `cargo test --features ssr`.
+
+#![cfg(feature = "ssr")]
+
+mod common;
+
+use common::{member_user, mock_access, super_user, workspace_model, TestContext};
+use end_2_end_demo::dto::workspace_dto::WorkspaceDto;
+use end_2_end_demo::errors::workspace_error::WorkspaceError;
+use end_2_end_demo::server_fn::workspace::{
+    add_or_update_workspace, delete_workspace, get_workspaces,
+};
+use sea_orm::MockExecResult;
+
+fn workspace_dto(id: i64, name: &str, owner_id: i64) -> WorkspaceDto {
+    WorkspaceDto::new(id, name.to_string(), owner_id, None)
+}
+
+#[tokio::test]
+async fn mock_anonymous_cannot_use_workspaces() {
+    let ctx = TestContext::mock(TestContext::mock_db());
+
+    assert_eq!(get_workspaces().await, Err(WorkspaceError::Unauthorized));
+    assert_eq!(
+        add_or_update_workspace(workspace_dto(-1, "mine", 7)).await,
+        Err(WorkspaceError::Unauthorized)
+    );
+    assert_eq!(delete_workspace(0).await, Err(WorkspaceError::Unauthorized));
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn mock_list_visible_workspaces() {
+    let ctx = TestContext::mock(
+        mock_access(&member_user(), &[])
+            .append_query_results([vec![workspace_model(0, super_user().id)]]),
+    );
+    ctx.login_as(member_user());
+
+    let workspaces = get_workspaces().await.unwrap();
+
+    assert_eq!(
+        workspaces.iter().map(|ws| ws.id).collect::<Vec<_>>(),
+        vec![0]
+    );
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn mock_new_workspace_is_owned_by_caller() {
+    let caller = member_user();
+    let ctx = TestContext::mock(
+        mock_access(&caller, &[]).append_query_results([vec![workspace_model(3, caller.id)]]),
+    );
+    ctx.login_as(caller.clone());
+
+    // the submitted owner_id is ignored
+    let ws = add_or_update_workspace(workspace_dto(-1, "mine", super_user().id))
+        .await
+        .unwrap();
+
+    assert_eq!(ws.id, 3);
+    assert_eq!(ws.owner_id, caller.id);
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn mock_owner_renames_workspace() {
+    let ctx = TestContext::mock(
+        mock_access(&super_user(), &[0]).append_query_results([vec![workspace_model(0, 0)]]),
+    );
+    ctx.login_as(super_user());
+    let changes = ctx.state().subscribe_workspace_changes();
+
+    let ws = add_or_update_workspace(workspace_dto(0, "ws-0", 7))
+        .await
+        .unwrap();
+
+    assert_eq!(ws.owner_id, 0);
+    // open lists and SSE listeners pick up the new name
+    assert!(changes.has_changed().unwrap());
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn mock_invalid_name_is_a_field_error() {
+    for name in ["", "   ", &"a".repeat(WorkspaceDto::NAME_MAX_LEN + 1)] {
+        for id in [-1, 0] {
+            // 校验失败时不会访问分组表
+            let ctx = TestContext::mock(mock_access(&super_user(), &[0]));
+            ctx.login_as(super_user());
+
+            match add_or_update_workspace(workspace_dto(id, name, 0)).await {
+                Err(WorkspaceError::Validation(errors)) => {
+                    assert!(errors.contains_key("name"), "{:?}", name)
+                }
+                rt => panic!("expected a validation error for {:?}, got {:?}", name, rt),
+            }
+            ctx.cleanup().await;
+        }
+    }
+}
+
+#[tokio::test]
+async fn mock_member_cannot_change_own_workspace() {
+    let ctx = TestContext::mock(mock_access(&member_user(), &[]));
+    ctx.login_as(member_user());
+
+    assert_eq!(
+        add_or_update_workspace(workspace_dto(0, "taken over", member_user().id)).await,
+        Err(WorkspaceError::Forbidden)
+    );
+    ctx.cleanup().await;
+
+    let ctx = TestContext::mock(mock_access(&member_user(), &[]));
+    ctx.login_as(member_user());
+
+    assert_eq!(delete_workspace(0).await, Err(WorkspaceError::Forbidden));
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn mock_other_workspaces_are_not_found() {
+    let ctx = TestContext::mock(mock_access(&member_user(), &[]));
+    ctx.login_as(member_user());
+
+    assert_eq!(
+        add_or_update_workspace(workspace_dto(1, "taken over", member_user().id)).await,
+        Err(WorkspaceError::NotFound(1))
+    );
+    ctx.cleanup().await;
+
+    let ctx = TestContext::mock(mock_access(&member_user(), &[]));
+    ctx.login_as(member_user());
+
+    assert_eq!(delete_workspace(1).await, Err(WorkspaceError::NotFound(1)));
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn mock_owner_deletes_workspace() {
+    let ctx = TestContext::mock(mock_access(&super_user(), &[0, 1]).append_exec_results([
+        MockExecResult {
+            last_insert_id: 0,
+            rows_affected: 1,
+        },
+    ]));
+    ctx.login_as(super_user());
+
+    assert_eq!(delete_workspace(1).await, Ok(()));
+    ctx.cleanup().await;
+}
@@ -0,0 +1,26 @@
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+/// Time taken to answer a request, logged in `ms`, or in `us` below a millisecond.
+pub struct Latency(pub Duration);
+
+impl Display for Latency {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.0.as_millis() > 0 {
+            write!(f, "{} ms", self.0.as_millis())
+        } else {
+            write!(f, "{} us", self.0.as_micros())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_display() {
+        assert_eq!(Latency(Duration::from_millis(12)).to_string(), "12 ms");
+        assert_eq!(Latency(Duration::from_micros(250)).to_string(), "250 us");
+    }
+}
@@ -0,0 +1,6 @@
+//! Server-side building blocks shared by the Axum servers of the `SSR_PART_2` demos.
+
+pub mod latency;
+pub mod metrics;
+pub mod request_id;
+pub mod shutdown;
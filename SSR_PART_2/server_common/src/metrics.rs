@@ -0,0 +1,270 @@
+use std::collections::HashSet;
+use std::fmt::{Debug, Formatter};
+use std::sync::OnceLock;
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use axum::extract::{MatchedPath, Request};
+use axum::http::header::CONTENT_TYPE;
+use axum::response::{IntoResponse, Response};
+use futures::future::BoxFuture;
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGaugeVec, Opts, Registry, TextEncoder,
+};
+use server_fn::axum::server_fn_paths;
+use tower::{Layer, Service};
+
+/// Path prefix of the server function routes, see `#[server(prefix = ..)]`.
+const SERVER_FN_PREFIX: &str = "/api/";
+
+/// Label of the requests that did not match a route or a registered server function.
+const UNMATCHED: &str = "unmatched";
+
+/// Prometheus metrics of the server: HTTP requests, server function calls and database pool.
+///
+/// Requests are recorded by wrapping the router in [`Metrics::layer`], the pool gauges are set
+/// with [`Metrics::set_db_pool`] before [`Metrics::render`] writes everything in the Prometheus
+/// text format.
+#[derive(Clone)]
+pub struct Metrics {
+    registry: Registry,
+    http_requests: IntCounterVec,
+    http_latency: HistogramVec,
+    server_fn_calls: IntCounterVec,
+    server_fn_errors: IntCounterVec,
+    db_connections: IntGaugeVec,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let http_requests = IntCounterVec::new(
+            Opts::new("http_requests_total", "HTTP requests by route and status"),
+            &["method", "route", "status"],
+        )
+        .expect("valid http_requests_total metric");
+        let http_latency = HistogramVec::new(
+            HistogramOpts::new(
+                "http_request_duration_seconds",
+                "HTTP request latency by route and status",
+            ),
+            &["method", "route", "status"],
+        )
+        .expect("valid http_request_duration_seconds metric");
+        let server_fn_calls = IntCounterVec::new(
+            Opts::new("server_fn_calls_total", "Server function calls"),
+            &["server_fn"],
+        )
+        .expect("valid server_fn_calls_total metric");
+        let server_fn_errors = IntCounterVec::new(
+            Opts::new(
+                "server_fn_errors_total",
+                "Server function calls answered with an error status",
+            ),
+            &["server_fn"],
+        )
+        .expect("valid server_fn_errors_total metric");
+        let db_connections = IntGaugeVec::new(
+            Opts::new("db_pool_connections", "Database pool connections by state"),
+            &["state"],
+        )
+        .expect("valid db_pool_connections metric");
+
+        let registry = Registry::new();
+        for collector in [
+            Box::new(http_requests.clone()) as Box<dyn prometheus::core::Collector>,
+            Box::new(http_latency.clone()),
+            Box::new(server_fn_calls.clone()),
+            Box::new(server_fn_errors.clone()),
+            Box::new(db_connections.clone()),
+        ] {
+            registry
+                .register(collector)
+                .expect("metric is registered once");
+        }
+
+        Self {
+            registry,
+            http_requests,
+            http_latency,
+            server_fn_calls,
+            server_fn_errors,
+            db_connections,
+        }
+    }
+
+    /// Tower layer recording every request going through the wrapped service.
+    pub fn layer(&self) -> MetricsLayer {
+        MetricsLayer {
+            metrics: self.clone(),
+        }
+    }
+
+    /// Sets the number of database pool connections in use and idle.
+    pub fn set_db_pool(&self, in_use: u32, idle: u32) {
+        self.db_connections
+            .with_label_values(&["in_use"])
+            .set(in_use.into());
+        self.db_connections
+            .with_label_values(&["idle"])
+            .set(idle.into());
+    }
+
+    /// All metrics in the Prometheus text format.
+    pub fn render(&self) -> String {
+        let mut buffer = vec![];
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .expect("metrics are encoded");
+        String::from_utf8(buffer).expect("metrics are UTF-8")
+    }
+
+    /// Response of the `/metrics` route.
+    pub fn response(&self) -> Response {
+        (
+            [(CONTENT_TYPE, TextEncoder::new().format_type().to_string())],
+            self.render(),
+        )
+            .into_response()
+    }
+
+    fn record(&self, method: &str, route: &str, path: &str, status: u16, seconds: f64) {
+        let status_label = status.to_string();
+        let labels = [method, route, status_label.as_str()];
+        self.http_requests.with_label_values(&labels).inc();
+        self.http_latency
+            .with_label_values(&labels)
+            .observe(seconds);
+
+        if let Some(name) = server_fn_name(path) {
+            // 只有注册过的 server fn 用自己的名字，其它 /api/ 路径共用一个 label
+            let name = if is_server_fn(path) { name } else { UNMATCHED };
+            self.server_fn_calls.with_label_values(&[name]).inc();
+            if status >= 400 {
+                self.server_fn_errors.with_label_values(&[name]).inc();
+            }
+        }
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for Metrics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Metrics").finish_non_exhaustive()
+    }
+}
+
+/// See [`Metrics::layer`].
+#[derive(Debug, Clone)]
+pub struct MetricsLayer {
+    metrics: Metrics,
+}
+
+impl<S> Layer<S> for MetricsLayer {
+    type Service = MetricsService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        MetricsService {
+            inner,
+            metrics: self.metrics.clone(),
+        }
+    }
+}
+
+/// See [`Metrics::layer`].
+#[derive(Debug, Clone)]
+pub struct MetricsService<S> {
+    inner: S,
+    metrics: Metrics,
+}
+
+impl<S> Service<Request> for MetricsService<S>
+where
+    S: Service<Request, Response = Response> + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Response, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        let method = request.method().to_string();
+        let path = request.uri().path().to_string();
+        // 用路由模板而不是实际路径作为 label，避免 label 数量无限增长
+        let route = request
+            .extensions()
+            .get::<MatchedPath>()
+            .map(|route| route.as_str().to_string())
+            .unwrap_or_else(|| UNMATCHED.to_string());
+        let metrics = self.metrics.clone();
+        let start = Instant::now();
+        let response = self.inner.call(request);
+
+        Box::pin(async move {
+            let response = response.await?;
+            metrics.record(
+                &method,
+                &route,
+                &path,
+                response.status().as_u16(),
+                start.elapsed().as_secs_f64(),
+            );
+            Ok(response)
+        })
+    }
+}
+
+/// The name of the server function served at `path`, whose last segment is the function name
+/// followed by a hash of its location, e.g. `get_users_sig` for `/api/get_users_sig1234567890`.
+fn server_fn_name(path: &str) -> Option<&str> {
+    let name = path
+        .strip_prefix(SERVER_FN_PREFIX)?
+        .trim_end_matches(|c: char| c.is_ascii_digit());
+    (!name.is_empty() && !name.contains('/')).then_some(name)
+}
+
+/// Whether `path` is the path of a registered server function.
+///
+/// The paths are read on the first call: server functions are registered when the binary starts.
+fn is_server_fn(path: &str) -> bool {
+    static PATHS: OnceLock<HashSet<&'static str>> = OnceLock::new();
+    PATHS
+        .get_or_init(|| server_fn_paths().map(|(path, _)| path).collect())
+        .contains(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_server_fn_name() {
+        assert_eq!(
+            server_fn_name("/api/get_users_sig1234567890"),
+            Some("get_users_sig")
+        );
+        assert_eq!(server_fn_name("/api/move_users"), Some("move_users"));
+        assert_eq!(server_fn_name("/api/123"), None);
+        assert_eq!(server_fn_name("/api/a/b"), None);
+        assert_eq!(server_fn_name("/users/export.csv"), None);
+    }
+
+    #[test]
+    fn test_unregistered_server_fn_is_unmatched() {
+        let metrics = Metrics::new();
+        metrics.record("POST", "/api/{*fn_name}", "/api/random1", 404, 0.1);
+        metrics.record("POST", "/api/{*fn_name}", "/api/other2", 404, 0.1);
+
+        let rendered = metrics.render();
+        assert!(rendered.contains("server_fn_calls_total{server_fn=\"unmatched\"} 2"));
+        assert!(!rendered.contains("random"));
+    }
+}
@@ -0,0 +1,77 @@
+use axum::extract::Request;
+use axum::http::{HeaderName, HeaderValue};
+use axum::middleware::Next;
+use axum::response::Response;
+use leptos::prelude::{provide_context, use_context};
+
+/// Header carrying the id of a request, accepted from the client and echoed in the response.
+pub static X_REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Longest request id accepted from the client.
+const MAX_REQUEST_ID_LEN: usize = 64;
+
+/// Id of the current request, stored in the request extensions by [`set_request_id`] and provided
+/// to server functions by [`provide_request_id`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestId(pub String);
+
+/// Takes the request id from the `X-Request-Id` header, or generates one if it is missing or
+/// invalid, and echoes it in the response.
+pub async fn set_request_id(mut request: Request, next: Next) -> Response {
+    let id = request
+        .headers()
+        .get(&X_REQUEST_ID)
+        .and_then(|value| value.to_str().ok())
+        .filter(|id| is_valid_request_id(id))
+        .map(str::to_string)
+        .unwrap_or_else(|| xid::new().to_string());
+    let value = HeaderValue::from_str(&id).expect("request id is a valid header value");
+
+    request
+        .headers_mut()
+        .insert(X_REQUEST_ID.clone(), value.clone());
+    request.extensions_mut().insert(RequestId(id));
+    let mut response = next.run(request).await;
+    response.headers_mut().insert(X_REQUEST_ID.clone(), value);
+    response
+}
+
+/// Provides the [`RequestId`] of the current request to server functions; call it in the
+/// additional context of the Leptos routes.
+pub fn provide_request_id() {
+    let id = use_context::<axum::http::request::Parts>()
+        .and_then(|parts| parts.extensions.get::<RequestId>().cloned());
+    if let Some(id) = id {
+        provide_context(id);
+    }
+}
+
+/// Returns the id of the request a server function is running for, if any.
+pub fn current_request_id() -> Option<String> {
+    use_context::<RequestId>().map(|id| id.0)
+}
+
+/// Request ids from the client end up in logs, so only short ids of letters, digits, `-`, `_` and
+/// `.` are accepted.
+fn is_valid_request_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_REQUEST_ID_LEN
+        && id
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid_request_id() {
+        assert!(is_valid_request_id("cv3b2fm1s1qc73b6nfeg"));
+        assert!(is_valid_request_id("0d1f-42_a.b"));
+        assert!(!is_valid_request_id(""));
+        assert!(!is_valid_request_id("bad id"));
+        assert!(!is_valid_request_id("id\nforged log line"));
+        assert!(!is_valid_request_id(&"a".repeat(MAX_REQUEST_ID_LEN + 1)));
+    }
+}
@@ -0,0 +1,165 @@
+use std::future::{Future, IntoFuture};
+use std::time::Duration;
+
+use axum::body::{Body, HttpBody};
+use axum::extract::{Request, State};
+use axum::http::StatusCode;
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::Router;
+use futures::StreamExt;
+use tokio::net::TcpListener;
+use tokio::sync::{oneshot, watch};
+
+/// How long the connections may take to close once their requests are cancelled, before they are
+/// dropped.
+const CANCEL_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Resolves when the process receives Ctrl+C (SIGINT) or, on unix, SIGTERM.
+pub async fn signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("failed to install the Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("failed to install the SIGTERM handler")
+            .recv()
+            .await;
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => tracing::info!("Received SIGINT"),
+        _ = terminate => tracing::info!("Received SIGTERM"),
+    }
+}
+
+/// Serves `app` on `listener` until a shutdown [`signal`]; see [`serve_until`].
+pub async fn serve(
+    listener: TcpListener,
+    app: Router,
+    pre_stop_delay: Duration,
+    drain_timeout: Duration,
+    on_shutdown: impl FnOnce() + Send + 'static,
+) -> std::io::Result<()> {
+    serve_until(
+        listener,
+        app,
+        signal(),
+        pre_stop_delay,
+        drain_timeout,
+        on_shutdown,
+    )
+    .await
+}
+
+/// Serves `app` on `listener` until `shutdown` resolves, then:
+///
+/// 1. calls `on_shutdown`, which should make the readiness check fail,
+/// 2. keeps accepting connections for `pre_stop_delay`, so the load balancer notices and routes
+///    new requests to other instances first,
+/// 3. stops accepting connections and waits at most `drain_timeout` for the in-flight requests.
+///
+/// Requests still running after `drain_timeout` are cancelled: their handlers are dropped and
+/// their response bodies end, so they no longer use resources such as the database pool once this
+/// returns. Connections that still do not close are dropped.
+pub async fn serve_until(
+    listener: TcpListener,
+    app: Router,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+    pre_stop_delay: Duration,
+    drain_timeout: Duration,
+    on_shutdown: impl FnOnce() + Send + 'static,
+) -> std::io::Result<()> {
+    let (cancel, cancelled) = watch::channel(false);
+    let app = app.layer(middleware::from_fn_with_state(cancelled, cancel_requests));
+
+    let (signalled, on_signal) = oneshot::channel();
+    let shutdown = async move {
+        shutdown.await;
+        on_shutdown();
+        if !pre_stop_delay.is_zero() {
+            tracing::info!(
+                "Draining, still accepting connections for {}s......",
+                pre_stop_delay.as_secs_f32()
+            );
+            tokio::time::sleep(pre_stop_delay).await;
+        }
+        tracing::info!("Shutting down, no longer accepting connections......");
+        let _ = signalled.send(());
+    };
+    let mut server = tokio::spawn(
+        axum::serve(listener, app.into_make_service())
+            .with_graceful_shutdown(shutdown)
+            .into_future(),
+    );
+
+    // 没有收到信号时服务不会结束，除非监听出错
+    tokio::select! {
+        result = &mut server => return result.map_err(std::io::Error::other)?,
+        _ = on_signal => {}
+    }
+
+    tracing::info!(
+        "Waiting up to {}s for in-flight requests to finish......",
+        drain_timeout.as_secs_f32()
+    );
+    match tokio::time::timeout(drain_timeout, &mut server).await {
+        Ok(result) => {
+            result.map_err(std::io::Error::other)??;
+            tracing::info!("All in-flight requests finished");
+        }
+        Err(_) => {
+            tracing::warn!(
+                "Cancelling the in-flight requests still running after {}s",
+                drain_timeout.as_secs_f32()
+            );
+            cancel.send_replace(true);
+            // 中止 serve 任务不会结束 axum 为每个连接启动的任务，先取消请求让连接自行关闭
+            if tokio::time::timeout(CANCEL_TIMEOUT, &mut server)
+                .await
+                .is_err()
+            {
+                server.abort();
+                tracing::warn!(
+                    "Connections still open after cancelling their requests are dropped"
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Answers `503 Service Unavailable` instead of running the request once `cancelled` is set, and
+/// ends response bodies that are still streamed then.
+async fn cancel_requests(
+    State(cancelled): State<watch::Receiver<bool>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let until_cancelled = |mut cancelled: watch::Receiver<bool>| async move {
+        // 发送端被丢弃时 serve 已经返回，同样视为取消
+        let _ = cancelled.wait_for(|cancelled| *cancelled).await;
+    };
+
+    let response = tokio::select! {
+        response = next.run(request) => response,
+        _ = until_cancelled(cancelled.clone()) => {
+            return StatusCode::SERVICE_UNAVAILABLE.into_response();
+        }
+    };
+    // 长度已知的响应体已经生成完毕，只有流式的响应体需要取消
+    if response.body().size_hint().exact().is_some() {
+        return response;
+    }
+    let (parts, body) = response.into_parts();
+    let body = body
+        .into_data_stream()
+        .take_until(until_cancelled(cancelled));
+    Response::from_parts(parts, Body::from_stream(body))
+}
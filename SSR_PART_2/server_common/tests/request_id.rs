@@ -0,0 +1,74 @@
+//! Integration tests for the request id middleware in `request_id.rs`.
+
+use axum::body::{to_bytes, Body};
+use axum::extract::Request;
+use axum::http::request::Parts;
+use axum::routing::get;
+use axum::{middleware, Extension, Router};
+use leptos::prelude::{provide_context, Owner};
+use server_common::request_id::{
+    current_request_id, provide_request_id, set_request_id, RequestId, X_REQUEST_ID,
+};
+use tower::ServiceExt;
+
+/// Sends a request through [`set_request_id`] to a handler answering with the id it was given,
+/// and returns the id echoed in the `X-Request-Id` header and the one seen by the handler.
+async fn send(header: Option<&str>) -> (String, String) {
+    let app = Router::new()
+        .route(
+            "/",
+            get(|Extension(id): Extension<RequestId>| async move { id.0 }),
+        )
+        .layer(middleware::from_fn(set_request_id));
+
+    let mut request = Request::builder().uri("/");
+    if let Some(header) = header {
+        request = request.header(&X_REQUEST_ID, header);
+    }
+    let response = app
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+
+    let echoed = response.headers()[&X_REQUEST_ID]
+        .to_str()
+        .unwrap()
+        .to_string();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (echoed, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn request_id_from_client_is_kept() {
+    let (echoed, seen) = send(Some("client-id.1")).await;
+    assert_eq!(echoed, "client-id.1");
+    assert_eq!(seen, "client-id.1");
+}
+
+#[tokio::test]
+async fn missing_or_invalid_request_id_is_generated() {
+    let (echoed, seen) = send(None).await;
+    assert!(!echoed.is_empty());
+    assert_eq!(echoed, seen);
+
+    let (echoed, seen) = send(Some("not a valid id")).await;
+    assert_ne!(echoed, "not a valid id");
+    assert_eq!(echoed, seen);
+
+    // 每个请求的 id 都不同
+    assert_ne!(send(None).await.0, send(None).await.0);
+}
+
+#[test]
+fn request_id_is_provided_to_server_functions() {
+    let owner = Owner::new();
+    owner.with(|| {
+        assert_eq!(current_request_id(), None);
+
+        let (mut parts, _) = Request::new(Body::empty()).into_parts();
+        parts.extensions.insert(RequestId("abc".to_string()));
+        provide_context::<Parts>(parts);
+        provide_request_id();
+        assert_eq!(current_request_id(), Some("abc".to_string()));
+    });
+}
@@ -0,0 +1,87 @@
+//! Integration tests for the graceful shutdown in `shutdown.rs`.
+
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::routing::get;
+use axum::Router;
+use server_common::shutdown::serve_until;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+
+/// Sets its flag when dropped, i.e. when the handler holding it finishes or is cancelled.
+struct DropFlag(Arc<AtomicBool>);
+
+impl Drop for DropFlag {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Sends `GET path` and returns the status line of the response, or an empty string if the
+/// connection is closed without one.
+async fn get_status(addr: SocketAddr, path: &str) -> String {
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    stream
+        .write_all(
+            format!("GET {path} HTTP/1.1\r\nHost: test\r\nConnection: close\r\n\r\n").as_bytes(),
+        )
+        .await
+        .unwrap();
+    let mut response = String::new();
+    let _ = stream.read_to_string(&mut response).await;
+    response.lines().next().unwrap_or_default().to_string()
+}
+
+#[tokio::test]
+async fn shutdown_drains_then_cancels_requests() {
+    let slow_dropped = Arc::new(AtomicBool::new(false));
+    let app = Router::new().route("/", get(|| async { "ok" })).route(
+        "/slow",
+        get({
+            let slow_dropped = slow_dropped.clone();
+            move || async move {
+                let _flag = DropFlag(slow_dropped);
+                tokio::time::sleep(Duration::from_secs(60)).await;
+                "done"
+            }
+        }),
+    );
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let draining = Arc::new(AtomicBool::new(false));
+    let (signal, on_signal) = oneshot::channel::<()>();
+    let server = tokio::spawn(serve_until(
+        listener,
+        app,
+        async move {
+            let _ = on_signal.await;
+        },
+        Duration::from_millis(300),
+        Duration::from_millis(200),
+        {
+            let draining = draining.clone();
+            move || draining.store(true, Ordering::SeqCst)
+        },
+    ));
+
+    let slow = tokio::spawn(get_status(addr, "/slow"));
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    signal.send(()).unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    // 先进入 draining，在 pre-stop 延迟内仍然接受新连接
+    assert!(draining.load(Ordering::SeqCst));
+    assert_eq!(get_status(addr, "/").await, "HTTP/1.1 200 OK");
+    assert!(!slow_dropped.load(Ordering::SeqCst));
+
+    // 超过 drain_timeout 仍在处理的请求被取消
+    server.await.unwrap().unwrap();
+    assert!(slow_dropped.load(Ordering::SeqCst));
+    assert_eq!(slow.await.unwrap(), "HTTP/1.1 503 Service Unavailable");
+    assert!(TcpStream::connect(addr).await.is_err());
+}
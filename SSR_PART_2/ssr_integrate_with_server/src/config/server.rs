@@ -0,0 +1,88 @@
+use std::env;
+
+/// Environment variable setting [`ServerConfig::request_timeout`], named like the
+/// `APP_SERVER__*` overrides of `end_2_end_demo`.
+const REQUEST_TIMEOUT_VAR: &str = "APP_SERVER__REQUEST_TIMEOUT";
+
+/// Environment variable setting [`ServerConfig::shutdown_timeout`].
+const SHUTDOWN_TIMEOUT_VAR: &str = "APP_SERVER__SHUTDOWN_TIMEOUT";
+
+/// HTTP server configuration, read from the environment.
+#[derive(Debug)]
+pub struct ServerConfig {
+    /// Maximum time to answer a request before it fails with `408 Request Timeout` (seconds).
+    ///
+    /// Default: `10`
+    request_timeout: u64,
+    /// Maximum time to wait for in-flight requests after a shutdown signal (seconds).
+    ///
+    /// Default: `30`
+    shutdown_timeout: u64,
+}
+
+impl ServerConfig {
+    /// Reads the configuration from the environment, failing on invalid values.
+    pub fn from_env() -> Result<Self, String> {
+        Self::parse(
+            env::var(REQUEST_TIMEOUT_VAR).ok().as_deref(),
+            env::var(SHUTDOWN_TIMEOUT_VAR).ok().as_deref(),
+        )
+    }
+
+    fn parse(
+        request_timeout: Option<&str>,
+        shutdown_timeout: Option<&str>,
+    ) -> Result<Self, String> {
+        Ok(Self {
+            request_timeout: parse_seconds(REQUEST_TIMEOUT_VAR, request_timeout, 10)?,
+            shutdown_timeout: parse_seconds(SHUTDOWN_TIMEOUT_VAR, shutdown_timeout, 30)?,
+        })
+    }
+
+    pub fn request_timeout(&self) -> u64 {
+        self.request_timeout
+    }
+
+    pub fn shutdown_timeout(&self) -> u64 {
+        self.shutdown_timeout
+    }
+}
+
+/// Parses the number of seconds set in `var`, which must be greater than 0.
+fn parse_seconds(var: &str, value: Option<&str>, default: u64) -> Result<u64, String> {
+    let seconds = match value {
+        Some(value) => value
+            .trim()
+            .parse()
+            .map_err(|_| format!("{var} must be a number of seconds, got {value:?}"))?,
+        None => default,
+    };
+    if seconds == 0 {
+        return Err(format!("{var} must be greater than 0"));
+    }
+    Ok(seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timeouts() {
+        let config = ServerConfig::parse(None, None).unwrap();
+        assert_eq!(config.request_timeout(), 10);
+        assert_eq!(config.shutdown_timeout(), 30);
+
+        let config = ServerConfig::parse(Some("5"), Some("60")).unwrap();
+        assert_eq!(config.request_timeout(), 5);
+        assert_eq!(config.shutdown_timeout(), 60);
+
+        assert!(ServerConfig::parse(Some("0"), None)
+            .unwrap_err()
+            .contains("APP_SERVER__REQUEST_TIMEOUT must be greater than 0"));
+        assert!(ServerConfig::parse(None, Some("0"))
+            .unwrap_err()
+            .contains("APP_SERVER__SHUTDOWN_TIMEOUT must be greater than 0"));
+        assert!(ServerConfig::parse(Some("soon"), None).is_err());
+    }
+}
@@ -19,46 +19,77 @@ async fn main() {
     tracing_init::init();
     tracing::info!("Starting the application server......");
 
+    use ssr_integrate_with_server::my_config::server::ServerConfig;
+    let server_config = ServerConfig::from_env().expect("invalid server configuration");
+
     // request timeout, default 10s
     use axum::http::StatusCode;
     use std::time::Duration;
     let timeout = TimeoutLayer::with_status_code(
         StatusCode::REQUEST_TIMEOUT, // 408
-        Duration::from_secs(10),
+        Duration::from_secs(server_config.request_timeout()),
     );
 
     // request path, latency and status tracing.
     use axum::extract::Request;
+    use server_common::request_id::{provide_request_id, set_request_id, RequestId};
     use ssr_integrate_with_server::middleware::tracing_request::LatencyOnResponse;
     use tower_http::trace::TraceLayer;
     let tracing = TraceLayer::new_for_http()
         .make_span_with(|request: &Request| {
             let method = request.method();
             let path = request.uri().path();
-            let id = xid::new(); // Generate unique request ID
+            // set_request_id 在 TraceLayer 之前执行，已经放入了 request id
+            let id = request
+                .extensions()
+                .get::<RequestId>()
+                .map(|id| id.0.as_str())
+                .unwrap_or_default();
             tracing::info_span!("Api Request: ", id = %id, method = %method, path = %path)
         })
         .on_request(())
         .on_failure(())
         .on_response(LatencyOnResponse);
 
+    // Prometheus 指标：/metrics
+    use server_common::metrics::Metrics;
+    let metrics = Metrics::new();
+    let metrics_layer = metrics.layer();
+
     let app = Router::new()
-        .leptos_routes(&leptos_options, routes, {
-            let leptos_options = leptos_options.clone();
-            move || shell(leptos_options.clone())
-        })
+        .leptos_routes_with_context(
+            &leptos_options,
+            routes,
+            provide_request_id, // 让 server fn 可以拿到当前请求的 X-Request-Id
+            {
+                let leptos_options = leptos_options.clone();
+                move || shell(leptos_options.clone())
+            },
+        )
+        .route(
+            "/metrics",
+            axum::routing::get(move || std::future::ready(metrics.response())),
+        )
         .layer(timeout)
         .layer(tracing)
+        .layer(axum::middleware::from_fn(set_request_id))
         .fallback(leptos_axum::file_and_error_handler(shell))
-        .with_state(leptos_options);
+        .with_state(leptos_options)
+        // 在 fallback 之后添加，未匹配路由的请求也会被记录
+        .layer(metrics_layer);
 
     // run our app with hyper
     // `axum::Server` is a re-export of `hyper::Server`
     log!("listening on http://{}", &addr);
     let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
-    axum::serve(listener, app.into_make_service())
+    // graceful shutdown on SIGTERM/SIGINT, wait at most shutdown_timeout for in-flight requests
+    use server_common::shutdown;
+    let drain_timeout = Duration::from_secs(server_config.shutdown_timeout());
+    // 没有就绪检查，不需要在关闭监听前等待负载均衡摘除
+    shutdown::serve(listener, app, Duration::ZERO, drain_timeout, || {})
         .await
         .unwrap();
+    tracing::info!("Server stopped");
 }
 
 #[cfg(not(feature = "ssr"))]
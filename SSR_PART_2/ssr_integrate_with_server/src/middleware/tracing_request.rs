@@ -1,7 +1,7 @@
 #![cfg(feature = "ssr")]
 
 use axum::http::Response;
-use std::fmt::{Display, Formatter};
+use server_common::latency::Latency;
 use std::time::Duration;
 use tower_http::trace::OnResponse;
 use tracing::Span;
@@ -10,8 +10,6 @@ use tracing::Span;
 #[derive(Debug, Clone)]
 pub struct LatencyOnResponse;
 
-pub struct Latency(Duration);
-
 impl<B> OnResponse<B> for LatencyOnResponse {
     fn on_response(self, response: &Response<B>, latency: Duration, _span: &Span) {
         tracing::info!(
@@ -21,13 +19,3 @@ impl<B> OnResponse<B> for LatencyOnResponse {
         )
     }
 }
-
-impl Display for Latency {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        if self.0.as_millis() > 0 {
-            write!(f, "{} ms", self.0.as_millis())
-        } else {
-            write!(f, "{} us", self.0.as_micros())
-        }
-    }
-}